@@ -28,20 +28,38 @@ mod cplib {
         import!("ds/segtree");
         import!("ds/csr");
         
+        // import!("ds/rollback_unionfind");
+        // import!("ds/persistent_unionfind");
+        
         // import!("ds/persistent_segtree");
+        // import!("ds/pointer_segtree");
+        // import!("ds/persistent_treap");
+        // import!("ds/persistent_array");
+        // import!("ds/persistent_queue");
+        // import!("ds/fenwick");
+        // import!("ds/segtree_2d");
+        // import!("ds/sparse_table");
+        // import!("ds/wavelet_matrix");
+        // import!("ds/fenwick_2d");
+        // import!("ds/bitset");
         
         
         // import!("ds/trie");
         // import!("ds/binary_trie");
+        // import!("ds/interval_heap");
+        // import!("ds/meldable_heap");
+        // import!("ds/rangeset");
+        // import!("ds/multiset");
         // import!("ds/sorted_set");
         // import!("ds/foldable_deque");
         // import!("ds/splay_tree");
+        // import!("ds/ordered");
+        // import!("ds/link_cut_tree");
+        // import!("ds/euler_tour_tree");
         // import!("ds/sparse_segtree");
         // import!("ds/rolling_hash_deque");
         
-        // import!("ds/ordered");
-        
-        // import!("ds/multiset");
+        // import!("ds/li_chao_tree");
         // import!("ds/convex_hull_trick");
     }
     
@@ -53,6 +71,8 @@ mod cplib {
         // import!("algo/bellman_ford");
         // import!("algo/warshall_floyd");
         // import!("algo/lcs");
+        
+        // import!("algo/mo");
     }
     
     pub mod graph {
@@ -87,6 +107,7 @@ mod cplib {
         import!("traits/iter_util");
         import!("traits/map_init");
         import!("traits/vec_split");
+        import!("traits/select");
     }
     
     pub mod util {
@@ -94,6 +115,7 @@ mod cplib {
         import!("util/macros");
         import!("util/func");
         import!("util/debug");
+        import!("util/hash");
         // import!("util/global");
         
         // import!("util/input");
@@ -0,0 +1,249 @@
+//! Mo's algorithm
+//! 
+//! 区間 `[l, r)` に対するクエリを、区間の端を 1 ずつ動かして答える。
+//! 
+//! - [`Mo`]: クエリを Hilbert order で並べる。`N` を列の長さ、`Q` をクエリ数として `O(N sqrt(Q))` 回の伸縮を行う。
+//! - [`MoWithUpdate`]: 一点更新を含む Mo (時刻を 3 つ目の軸とする)。`O(N^(5/3))` 回程度の伸縮と更新を行う。
+//! - [`MoTree`]: 木のパスに対する Mo。[`Tree::euler_order`] 上の区間に帰着する。
+
+use std::ops::RangeBounds;
+use crate::cplib::{graph::tree::Tree, util::func::to_bounds};
+
+
+
+/// Mo's algorithm の状態。管理している区間を `[l, r)` とする。
+pub trait MoState {
+    type Answer;
+    /// 区間を `[l-1, r)` にする。引数は `l-1` である。
+    fn add_left(&mut self, i: usize);
+    /// 区間を `[l, r+1)` にする。引数は `r` である。
+    fn add_right(&mut self, i: usize);
+    /// 区間を `[l+1, r)` にする。引数は `l` である。
+    fn remove_left(&mut self, i: usize);
+    /// 区間を `[l, r-1)` にする。引数は `r-1` である。
+    fn remove_right(&mut self, i: usize);
+    /// 現在の区間に対する答えを返す。
+    fn answer(&mut self) -> Self::Answer;
+}
+
+/// 一点更新を含む Mo's algorithm の状態
+pub trait MoUpdate: MoState {
+    /// `t` 番目の更新を適用する。現在の区間は `[l, r)` である。
+    fn apply(&mut self, t: usize, l: usize, r: usize);
+    /// `t` 番目の更新を取り消す。現在の区間は `[l, r)` である。
+    fn revert(&mut self, t: usize, l: usize, r: usize);
+}
+
+/// 木の Mo's algorithm の状態。頂点の集合を管理する。
+pub trait MoTreeState {
+    type Answer;
+    fn add(&mut self, v: usize);
+    fn remove(&mut self, v: usize);
+    fn answer(&mut self) -> Self::Answer;
+}
+
+
+
+/// `2^log * 2^log` の格子上の Hilbert 曲線で `(x, y)` が何番目かを返す。
+pub fn hilbert_order(mut x: usize, mut y: usize, log: u32) -> u64 {
+    let n = 1 << log;
+    let mut d = 0;
+    let mut s = n >> 1;
+    while s > 0 {
+        let (rx, ry) = (x & s != 0, y & s != 0);
+        d += (s * s) as u64 * ((3 * rx as u64) ^ ry as u64);
+        if !ry {
+            if rx { x = n-1-x; y = n-1-y; }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s >>= 1;
+    }
+    d
+}
+
+/// 区間 `qs` を Hilbert order で並べた添字の列を返す。
+fn sort_hilbert(n: usize, qs: &[(usize, usize)]) -> Vec<usize> {
+    let log = (n+1).next_power_of_two().trailing_zeros();
+    let key: Vec<u64> = qs.iter().map(|&(l, r)| hilbert_order(l, r, log)).collect();
+    let mut ord: Vec<usize> = (0..qs.len()).collect();
+    ord.sort_unstable_by_key(|&i| key[i]);
+    ord
+}
+
+/// `[l, r)` を `[nl, nr)` にする。
+fn shift<S: MoState>(state: &mut S, (l, r): &mut (usize, usize), (nl, nr): (usize, usize)) {
+    while nl < *l { *l -= 1; state.add_left(*l); }
+    while *r < nr { state.add_right(*r); *r += 1; }
+    while *l < nl { state.remove_left(*l); *l += 1; }
+    while nr < *r { *r -= 1; state.remove_right(*r); }
+}
+
+
+
+/// Mo's algorithm
+/// 
+/// # Examples
+/// 
+/// ```ignore
+/// let mut mo = Mo::new(n);
+/// for &(l, r) in &queries { mo.push(l..r); }
+/// let ans = mo.run(&mut state);
+/// ```
+pub struct Mo {
+    n: usize,
+    queries: Vec<(usize, usize)>,
+}
+
+impl Mo {
+    /// 長さ `n` の列に対する Mo を作成する。
+    pub fn new(n: usize) -> Self { Self { n, queries: vec![] } }
+    
+    pub fn len(&self) -> usize { self.queries.len() }
+    
+    /// クエリ区間を追加する。
+    pub fn push(&mut self, range: impl RangeBounds<usize>) {
+        let [l, r] = to_bounds(range, self.n);
+        self.queries.push((l, r));
+    }
+    
+    /// 全てのクエリに答え、追加した順に並べて返す。`state` は空区間の状態である必要がある。
+    pub fn run<S: MoState>(&self, state: &mut S) -> Vec<S::Answer> {
+        let mut res: Vec<Option<S::Answer>> = (0..self.len()).map(|_| None).collect();
+        let mut cur = (0, 0);
+        for i in sort_hilbert(self.n, &self.queries) {
+            shift(state, &mut cur, self.queries[i]);
+            res[i] = Some(state.answer());
+        }
+        res.into_iter().map(Option::unwrap).collect()
+    }
+}
+
+
+
+/// 一点更新を含む Mo's algorithm
+/// 
+/// 更新は `0, 1, ...` の番号で [`MoUpdate`] が管理し、クエリは「何個目までの更新を適用した状態か」とともに追加する。
+/// 更新を位置と値の swap として実装すると、`apply` と `revert` が同じ処理になり簡単である。
+pub struct MoWithUpdate {
+    n: usize,
+    /// `(l, r, t)`
+    queries: Vec<(usize, usize, usize)>,
+}
+
+impl MoWithUpdate {
+    pub fn new(n: usize) -> Self { Self { n, queries: vec![] } }
+    
+    pub fn len(&self) -> usize { self.queries.len() }
+    
+    /// 最初の `t` 個の更新を適用した状態でのクエリ区間を追加する。
+    pub fn push(&mut self, range: impl RangeBounds<usize>, t: usize) {
+        let [l, r] = to_bounds(range, self.n);
+        self.queries.push((l, r, t));
+    }
+    
+    /// 全てのクエリに答え、追加した順に並べて返す。`state` は空区間かつ更新を適用していない状態である必要がある。
+    pub fn run<S: MoUpdate>(&self, state: &mut S) -> Vec<S::Answer> {
+        let b = ((self.n.max(1) as f64).powf(2. / 3.) as usize).max(1);
+        let mut ord: Vec<usize> = (0..self.len()).collect();
+        ord.sort_unstable_by_key(|&i| {
+            let (l, r, t) = self.queries[i];
+            let (bl, br) = (l / b, r / b);
+            (bl, if bl & 1 == 0 { br } else { !br }, if (bl ^ br) & 1 == 0 { t } else { !t })
+        });
+        
+        let mut res: Vec<Option<S::Answer>> = (0..self.len()).map(|_| None).collect();
+        let (mut cur, mut ct) = ((0, 0), 0);
+        for i in ord {
+            let (l, r, t) = self.queries[i];
+            shift(state, &mut cur, (l, r));
+            while ct < t { state.apply(ct, cur.0, cur.1); ct += 1; }
+            while t < ct { ct -= 1; state.revert(ct, cur.0, cur.1); }
+            res[i] = Some(state.answer());
+        }
+        res.into_iter().map(Option::unwrap).collect()
+    }
+}
+
+
+
+/// Euler Tour 上の位置の伸縮を、頂点の追加・削除に変換する。
+/// 区間に 1 回だけ現れる頂点が集合に含まれる。
+struct Toggle<'a, S: MoTreeState> {
+    state: &'a mut S,
+    order: &'a [usize],
+    inside: Vec<bool>,
+}
+
+impl<S: MoTreeState> Toggle<'_, S> {
+    fn flip(&mut self, i: usize) {
+        let v = self.order[i] % self.inside.len();
+        self.inside[v] = !self.inside[v];
+        if self.inside[v] { self.state.add(v); } else { self.state.remove(v); }
+    }
+}
+
+impl<S: MoTreeState> MoState for Toggle<'_, S> {
+    type Answer = S::Answer;
+    fn add_left(&mut self, i: usize) { self.flip(i); }
+    fn add_right(&mut self, i: usize) { self.flip(i); }
+    fn remove_left(&mut self, i: usize) { self.flip(i); }
+    fn remove_right(&mut self, i: usize) { self.flip(i); }
+    fn answer(&mut self) -> Self::Answer { self.state.answer() }
+}
+
+/// 木のパスに対する Mo's algorithm
+/// 
+/// パス `u - v` を [`Tree::euler_order`] 上の区間に変換する。LCA 以外のパス上の頂点は区間にちょうど 1 回現れ、
+/// それ以外の頂点は 0 回または 2 回現れる。LCA は必要に応じて答える直前に追加し、直後に削除する。
+/// 
+/// - `run`: パス上の頂点の集合に対するクエリに答える。
+/// - `run_edge`: パス上の辺の集合に対するクエリに答える。辺は子側の頂点 `v` (辺 `(par(v), v)`) で表す。
+pub struct MoTree<'a> {
+    tree: &'a Tree,
+    /// `(l, r, lca)`
+    queries: Vec<(usize, usize, usize)>,
+}
+
+impl<'a> MoTree<'a> {
+    pub fn new(tree: &'a Tree) -> Self { Self { tree, queries: vec![] } }
+    
+    pub fn len(&self) -> usize { self.queries.len() }
+    
+    /// パス `u - v` のクエリを追加する。
+    pub fn push(&mut self, mut u: usize, mut v: usize) {
+        let (t, n) = (self.tree, self.tree.len());
+        if t.euler(u) > t.euler(v) { std::mem::swap(&mut u, &mut v); }
+        let w = t.lca(u, v);
+        if w == u {
+            self.queries.push((t.euler(u), t.euler(v)+1, w));
+        } else {
+            self.queries.push((t.euler(n+u), t.euler(v)+1, w));
+        }
+    }
+    
+    /// パス上の頂点の集合に対するクエリに答え、追加した順に並べて返す。`state` は空集合の状態である必要がある。
+    pub fn run<S: MoTreeState>(&self, state: &mut S) -> Vec<S::Answer> { self.solve(state, false) }
+    
+    /// パス上の辺の集合に対するクエリに答え、追加した順に並べて返す。`state` は空集合の状態である必要がある。
+    pub fn run_edge<S: MoTreeState>(&self, state: &mut S) -> Vec<S::Answer> { self.solve(state, true) }
+    
+    fn solve<S: MoTreeState>(&self, state: &mut S, edge: bool) -> Vec<S::Answer> {
+        let n = self.tree.len();
+        // LCA がパスの端点のときは、区間に LCA が含まれる
+        let qs: Vec<(usize, usize)> = self.queries.iter().map(|&(l, r, w)| {
+            if edge && l == self.tree.euler(w) { (l+1, r) } else { (l, r) }
+        }).collect();
+        let mut toggle = Toggle { state, order: self.tree.euler_order(), inside: vec![false; n] };
+        let mut res: Vec<Option<S::Answer>> = (0..self.len()).map(|_| None).collect();
+        let mut cur = (0, 0);
+        for i in sort_hilbert(2*n, &qs) {
+            shift(&mut toggle, &mut cur, qs[i]);
+            let w = self.queries[i].2;
+            let extra = !edge && !toggle.inside[w];
+            if extra { toggle.state.add(w); }
+            res[i] = Some(toggle.state.answer());
+            if extra { toggle.state.remove(w); }
+        }
+        res.into_iter().map(Option::unwrap).collect()
+    }
+}
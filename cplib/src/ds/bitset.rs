@@ -0,0 +1,273 @@
+use std::{fmt::Debug, ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Index, Not, RangeBounds, Shl, ShlAssign, Shr, ShrAssign}};
+use crate::cplib::util::func::to_bounds;
+
+
+
+/// ビット `i` を `i+k` に移す。はみ出したビットは捨てる。
+fn shl_words(a: &mut [u64], k: usize) {
+    let (q, r, n) = (k / 64, k % 64, a.len());
+    for i in (0..n).rev() {
+        let mut v = if q <= i { a[i-q] << r } else { 0 };
+        if r != 0 && q < i { v |= a[i-q-1] >> (64-r); }
+        a[i] = v;
+    }
+}
+
+/// ビット `i` を `i-k` に移す。はみ出したビットは捨てる。
+fn shr_words(a: &mut [u64], k: usize) {
+    let (q, r, n) = (k / 64, k % 64, a.len());
+    for i in 0..n {
+        let mut v = if i+q < n { a[i+q] >> r } else { 0 };
+        if r != 0 && i+q+1 < n { v |= a[i+q+1] << (64-r); }
+        a[i] = v;
+    }
+}
+
+/// `i` 以上で最小の立っているビットを返す。
+fn find_from(a: &[u64], i: usize) -> Option<usize> {
+    let mut w = i / 64;
+    if a.len() <= w { return None; }
+    let mut x = a[w] & !0 << (i % 64);
+    loop {
+        if x != 0 { return Some(w * 64 + x.trailing_zeros() as usize); }
+        w += 1;
+        if w == a.len() { return None; }
+        x = a[w];
+    }
+}
+
+/// ビット `[l, r)` を含む各ワードに、該当部分のマスクとともに `f` を適用する。
+fn apply_range(a: &mut [u64], l: usize, r: usize, f: impl Fn(&mut u64, u64)) {
+    if l >= r { return; }
+    let (lw, rw) = (l / 64, (r-1) / 64);
+    let (lm, rm) = (!0 << (l % 64), !0 >> (63 - (r-1) % 64));
+    if lw == rw { f(&mut a[lw], lm & rm); return; }
+    f(&mut a[lw], lm);
+    for w in &mut a[lw+1..rw] { f(w, !0); }
+    f(&mut a[rw], rm);
+}
+
+
+
+/// 立っているビットの位置を昇順に返すイテレータ
+pub struct Ones<'a> {
+    words: &'a [u64],
+    idx: usize,
+    cur: u64,
+}
+
+impl Iterator for Ones<'_> {
+    type Item = usize;
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.cur == 0 {
+            self.idx += 1;
+            self.cur = *self.words.get(self.idx)?;
+        }
+        let res = self.idx * 64 + self.cur.trailing_zeros() as usize;
+        self.cur &= self.cur - 1;
+        Some(res)
+    }
+}
+
+
+
+macro_rules! impl_bitset {
+    ($t:ty $(, $n:ident)?) => {
+        impl$(<const $n: usize>)? $t {
+            pub fn get(&self, i: usize) -> bool {
+                assert!(i < self.len());
+                self.words()[i / 64] >> (i % 64) & 1 == 1
+            }
+            
+            pub fn set(&mut self, i: usize, b: bool) {
+                assert!(i < self.len());
+                let w = &mut self.words_mut()[i / 64];
+                if b { *w |= 1 << (i % 64); } else { *w &= !(1 << (i % 64)); }
+            }
+            
+            pub fn flip(&mut self, i: usize) {
+                assert!(i < self.len());
+                self.words_mut()[i / 64] ^= 1 << (i % 64);
+            }
+            
+            pub fn count_ones(&self) -> usize { self.words().iter().map(|w| w.count_ones() as usize).sum() }
+            pub fn any(&self) -> bool { self.words().iter().any(|&w| w != 0) }
+            pub fn clear(&mut self) { self.words_mut().fill(0); }
+            
+            /// 立っているビットのうち最小の位置を返す。
+            pub fn find_first(&self) -> Option<usize> { find_from(self.words(), 0) }
+            
+            /// 立っているビットのうち、`i` より大きい最小の位置を返す。
+            pub fn find_next(&self, i: usize) -> Option<usize> { find_from(self.words(), i+1) }
+            
+            /// 立っているビットの位置を昇順に返す。
+            pub fn iter(&self) -> Ones<'_> {
+                Ones { words: self.words(), idx: 0, cur: self.words().first().copied().unwrap_or(0) }
+            }
+            
+            pub fn set_range(&mut self, range: impl RangeBounds<usize>) {
+                let [l, r] = to_bounds(range, self.len());
+                apply_range(self.words_mut(), l, r, |w, m| *w |= m);
+            }
+            
+            pub fn reset_range(&mut self, range: impl RangeBounds<usize>) {
+                let [l, r] = to_bounds(range, self.len());
+                apply_range(self.words_mut(), l, r, |w, m| *w &= !m);
+            }
+            
+            pub fn flip_range(&mut self, range: impl RangeBounds<usize>) {
+                let [l, r] = to_bounds(range, self.len());
+                apply_range(self.words_mut(), l, r, |w, m| *w ^= m);
+            }
+        }
+        
+        impl$(<const $n: usize>)? Index<usize> for $t {
+            type Output = bool;
+            fn index(&self, index: usize) -> &Self::Output { if self.get(index) { &true } else { &false } }
+        }
+        
+        impl$(<const $n: usize>)? BitAndAssign<&$t> for $t {
+            fn bitand_assign(&mut self, rhs: &$t) {
+                assert!(self.len() == rhs.len());
+                for (a, b) in self.words_mut().iter_mut().zip(rhs.words()) { *a &= b; }
+            }
+        }
+        
+        impl$(<const $n: usize>)? BitOrAssign<&$t> for $t {
+            fn bitor_assign(&mut self, rhs: &$t) {
+                assert!(self.len() == rhs.len());
+                for (a, b) in self.words_mut().iter_mut().zip(rhs.words()) { *a |= b; }
+            }
+        }
+        
+        impl$(<const $n: usize>)? BitXorAssign<&$t> for $t {
+            fn bitxor_assign(&mut self, rhs: &$t) {
+                assert!(self.len() == rhs.len());
+                for (a, b) in self.words_mut().iter_mut().zip(rhs.words()) { *a ^= b; }
+            }
+        }
+        
+        impl$(<const $n: usize>)? BitAnd<&$t> for $t { type Output = $t; fn bitand(mut self, rhs: &$t) -> $t { self &= rhs; self } }
+        impl$(<const $n: usize>)? BitOr<&$t> for $t { type Output = $t; fn bitor(mut self, rhs: &$t) -> $t { self |= rhs; self } }
+        impl$(<const $n: usize>)? BitXor<&$t> for $t { type Output = $t; fn bitxor(mut self, rhs: &$t) -> $t { self ^= rhs; self } }
+        
+        impl$(<const $n: usize>)? ShlAssign<usize> for $t {
+            fn shl_assign(&mut self, rhs: usize) { shl_words(self.words_mut(), rhs); self.trim(); }
+        }
+        
+        impl$(<const $n: usize>)? ShrAssign<usize> for $t {
+            fn shr_assign(&mut self, rhs: usize) { shr_words(self.words_mut(), rhs); }
+        }
+        
+        impl$(<const $n: usize>)? Shl<usize> for $t { type Output = $t; fn shl(mut self, rhs: usize) -> $t { self <<= rhs; self } }
+        impl$(<const $n: usize>)? Shr<usize> for $t { type Output = $t; fn shr(mut self, rhs: usize) -> $t { self >>= rhs; self } }
+        
+        impl$(<const $n: usize>)? Not for $t {
+            type Output = $t;
+            fn not(mut self) -> $t {
+                for w in self.words_mut() { *w = !*w; }
+                self.trim();
+                self
+            }
+        }
+        
+        impl$(<const $n: usize>)? Debug for $t {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let s: String = (0..self.len()).map(|i| if self.get(i) { '1' } else { '0' }).collect();
+                write!(f, "{}", s)
+            }
+        }
+    };
+}
+
+
+
+/// 長さを実行時に決める bitset
+/// 
+/// 位置 `i` のビットは `words()[i/64]` の下から `i%64` 番目に格納される。`shl` は位置を増やす方向のシフトである。
+/// 
+/// # 搭載機能
+/// 
+/// - 一点取得・更新 `get, set, flip`
+/// - 区間更新 `set_range, reset_range, flip_range`
+/// - `count_ones, any, find_first, find_next, iter` (立っているビットの列挙)
+/// - `&, |, ^, !, <<, >>` (二項演算は同じ長さのもの同士のみ)
+/// 
+/// # Examples
+/// 
+/// 部分和 DP は次のように書ける。
+/// 
+/// ```ignore
+/// let mut dp = BitSet::new(s+1);
+/// dp.set(0, true);
+/// for &a in &v { dp |= &(dp.clone() << a); }
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    len: usize,
+    dat: Vec<u64>,
+}
+
+impl BitSet {
+    /// 長さ `len` ですべてのビットが `0` のものを作成する。
+    pub fn new(len: usize) -> Self {
+        Self { len, dat: vec![0; len.div_ceil(64)] }
+    }
+    
+    pub fn len(&self) -> usize { self.len }
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+    pub fn words(&self) -> &[u64] { &self.dat }
+    fn words_mut(&mut self) -> &mut [u64] { &mut self.dat }
+    
+    /// 長さ以上の位置のビットを `0` にする。
+    fn trim(&mut self) {
+        if self.len % 64 != 0 { *self.dat.last_mut().unwrap() &= (1 << (self.len % 64)) - 1; }
+    }
+    
+    /// 長さを `len` に変更する。増えた部分は `0` になる。
+    pub fn resize(&mut self, len: usize) {
+        self.len = len;
+        self.dat.resize(len.div_ceil(64), 0);
+        self.trim();
+    }
+}
+
+impl_bitset!(BitSet);
+
+impl FromIterator<bool> for BitSet {
+    fn from_iter<T: IntoIterator<Item = bool>>(iter: T) -> Self {
+        let mut res = Self::new(0);
+        for b in iter {
+            if res.len % 64 == 0 { res.dat.push(0); }
+            res.len += 1;
+            if b { res.set(res.len-1, true); }
+        }
+        res
+    }
+}
+
+
+
+/// 長さ `64*N` の固定長の bitset で、`Copy` である。機能は [`BitSet`] と同じ。
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FixedBitSet<const N: usize> {
+    dat: [u64; N],
+}
+
+impl<const N: usize> FixedBitSet<N> {
+    pub const LEN: usize = 64 * N;
+    
+    pub fn new() -> Self { Self { dat: [0; N] } }
+    
+    pub fn len(&self) -> usize { Self::LEN }
+    pub fn is_empty(&self) -> bool { N == 0 }
+    pub fn words(&self) -> &[u64] { &self.dat }
+    fn words_mut(&mut self) -> &mut [u64] { &mut self.dat }
+    fn trim(&mut self) {}
+}
+
+impl_bitset!(FixedBitSet<N>, N);
+
+impl<const N: usize> Default for FixedBitSet<N> {
+    fn default() -> Self { Self::new() }
+}
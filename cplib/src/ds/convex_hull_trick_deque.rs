@@ -1,35 +1,53 @@
-use std::collections::VecDeque;
+use std::{collections::VecDeque, marker::PhantomData};
+use crate::cplib::traits::select::Select;
 
 
-/// 最大値を取る区間の区切れを返す。すなわち、`x in [-inf, sep)` ならば左が最大で、`[sep, inf)` ならば右が最大である。
-fn sep(l: (i64, i64), r: (i64, i64)) -> i64 {
+/// 最大値を取る区間の区切れを返す。すなわち、`x in (-inf, sep]` ならば左が最大で、`(sep, inf)` ならば右が最大である。
+fn sep(l: (i64, i64), r: (i64, i64)) -> i128 {
     debug_assert!(l.0 < r.0);
-    (l.1 - r.1).div_euclid(r.0 - l.0)
+    (l.1 as i128 - r.1 as i128).div_euclid(r.0 as i128 - l.0 as i128)
 }
 
 
 
-/// 一次関数の集合に対して、点 `x` における最大値を求めることができる。
+/// 一次関数の集合に対して、点 `x` における最小値・最大値 (`S` で選ぶ) を求めることができる。
 /// 
 /// - 直線追加 (push_front/push_back): amortized `O(1)`
 /// - point max クエリ: `O(log N)`
 /// 
+/// 内部では `S = Min` のとき直線 `(a, b)` を `(-a, -b)` として最大値を求める。
+/// そのため、`push_back` する傾きは `Max` では広義単調増加、`Min` では広義単調減少でなければならない。
+/// 
+/// 計算は `i128` で行うので、`|a|, |b|, |x| <= 4e18` かつ答えが `i64` に収まれば安全である。
+/// 
 /// # Reference
 /// 
 /// - [クエリが整数の Convex Hull Trick の凸判定 (noshi91)](https://noshi91.hatenablog.com/entry/2021/03/23/200810)
 ///   + 最大値を求めるため、`max {ax+b >= cx+d}` の形になっている。
-#[derive(Debug)]
-pub struct ConvexHullTrick {
+#[derive(Debug, Clone)]
+pub struct ConvexHullTrick<S: Select> {
     dat: VecDeque<(i64, i64)>,
+    _marker: PhantomData<S>,
 }
 
-impl ConvexHullTrick {
+impl<S: Select> ConvexHullTrick<S> {
     pub fn new() -> Self {
-        Self { dat: VecDeque::new() }
+        Self { dat: VecDeque::new(), _marker: PhantomData }
+    }
+    
+    pub fn len(&self) -> usize { self.dat.len() }
+    pub fn is_empty(&self) -> bool { self.dat.is_empty() }
+    
+    /// 外部の直線と内部の直線を変換する。
+    fn conv((a, b): (i64, i64)) -> (i64, i64) {
+        if S::MAX { return (a, b); }
+        assert!(a != i64::MIN && b != i64::MIN, "i64::MIN cannot be negated (@ ConvexHullTrick)");
+        (-a, -b)
     }
     
-    /// `a` は末尾の傾き以上である必要がある。
+    /// `a` は末尾の傾き以上 (`Min` のときは以下) である必要がある。
     pub fn push_back(&mut self, a: i64, b: i64) {
+        let (a, b) = Self::conv((a, b));
         if let Some(r) = self.dat.back() {
             assert!(r.0 <= a);
             if r.0 == a {
@@ -47,7 +65,9 @@ impl ConvexHullTrick {
         self.dat.push_back((a, b));
     }
     
+    /// `a` は先頭の傾き以下 (`Min` のときは以上) である必要がある。
     pub fn push_front(&mut self, a: i64, b: i64) {
+        let (a, b) = Self::conv((a, b));
         if let Some(l) = self.dat.front() {
             assert!(a <= l.0);
             if l.0 == a {
@@ -57,21 +77,49 @@ impl ConvexHullTrick {
         }
         
         while self.dat.len() >= 2 {
-            let l = self.dat[1];
-            let r = self.dat[0];
+            let l = self.dat[0];
+            let r = self.dat[1];
             if sep((a, b), l) < sep(l, r) { break; }
             self.dat.pop_front();
         }
         self.dat.push_front((a, b));
     }
     
-    pub fn query(&self, x: i64) -> i64 {
+    /// 点 `x` で最も良い直線 `(a, b)` を返す。直線が存在しないとき `None` を返す。
+    pub fn query_line(&self, x: i64) -> Option<(i64, i64)> {
+        if self.dat.is_empty() { return None; }
         let (mut ok, mut ng) = (0, self.dat.len());
         while ng-ok > 1 {
             let i = (ok+ng)/2;
-            if x < sep(self.dat[i-1], self.dat[i]) { ng = i; } else { ok = i; }
+            if (x as i128) <= sep(self.dat[i-1], self.dat[i]) { ng = i; } else { ok = i; }
+        }
+        Some(Self::conv(self.dat[ok]))
+    }
+    
+    /// 点 `x` における最小値・最大値を返す。
+    /// 
+    /// # Panics
+    /// 
+    /// if `self` is empty or the answer overflows `i64`.
+    pub fn query(&self, x: i64) -> i64 {
+        let (a, b) = self.query_line(x).expect("empty (@ ConvexHullTrick::query)");
+        (a as i128 * x as i128 + b as i128).try_into().expect("overflow (@ ConvexHullTrick::query)")
+    }
+    
+    /// `self` と `other` の直線を全て持つものを返す。`O(N + M)`
+    pub fn merge(self, other: Self) -> Self {
+        let (mut l, mut r) = (self.dat.into_iter().peekable(), other.dat.into_iter().peekable());
+        let mut res = Self::new();
+        loop {
+            let next = match (l.peek(), r.peek()) {
+                (Some(x), Some(y)) => if x <= y { l.next() } else { r.next() },
+                (Some(_), None) => l.next(),
+                (None, Some(_)) => r.next(),
+                (None, None) => break
+            };
+            let (a, b) = Self::conv(next.unwrap());
+            res.push_back(a, b);
         }
-        let (a, b) = self.dat[ok];
-        a*x + b
+        res
     }
 }
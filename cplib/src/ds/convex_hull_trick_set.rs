@@ -1,16 +1,18 @@
-use std::{borrow::Borrow, collections::BTreeSet, fmt::Debug};
+use std::{borrow::Borrow, collections::BTreeSet, fmt::Debug, marker::PhantomData, ops::Bound};
+use crate::cplib::traits::select::Select;
 
 
 
+/// 内部の直線。`r` はこの直線が最大となる区間の右端 (閉) である。
 #[derive(Clone, Copy)]
 pub struct Line {
     a: i64,
     b: i64,
-    r: I64
+    r: I128
 }
 
 impl Line {
-    fn new(a: i64, b: i64, r: i64) -> Self { Self { a, b, r: I64(r)} }
+    fn new(a: i64, b: i64, r: i128) -> Self { Self { a, b, r: I128(r)} }
     fn ab(&self) -> (i64, i64) { (self.a, self.b) }
 }
 
@@ -19,7 +21,7 @@ impl Eq for Line {}
 impl PartialOrd for Line { fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> { Some(self.cmp(other)) } }
 impl Ord for Line { fn cmp(&self, other: &Self) -> std::cmp::Ordering { self.a.cmp(&other.a) } }
 impl Borrow<i64> for Line { fn borrow(&self) -> &i64 { &self.a } }
-impl Borrow<I64> for Line { fn borrow(&self) -> &I64 { &self.r } }
+impl Borrow<I128> for Line { fn borrow(&self) -> &I128 { &self.r } }
 impl Debug for Line {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "({}, {}, R={})", self.a, self.b, self.r.0)
@@ -27,77 +29,107 @@ impl Debug for Line {
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
-struct I64(i64);
+struct I128(i128);
 
 
 
-/// 最大値を取る区間の区切れを返す。すなわち、`x in [-inf, sep)` ならば左が最大で、`[sep, inf)` ならば右が最大である。
-fn sep(l: (i64, i64), r: (i64, i64)) -> i64 {
+/// 最大値を取る区間の区切れを返す。すなわち、`x in (-inf, sep]` ならば左が最大で、`(sep, inf)` ならば右が最大である。
+fn sep(l: (i64, i64), r: (i64, i64)) -> i128 {
     debug_assert!(l.0 < r.0);
-    (l.1 - r.1).div_euclid(r.0 - l.0)
+    (l.1 as i128 - r.1 as i128).div_euclid(r.0 as i128 - l.0 as i128)
 }
 
 
 
-/// 一次関数の集合に対して、点 `x` における最大値を求めることができる。<br>
-/// 一次関数 `(a, b)` やクエリ `x` について `-4e9 <= a, b, x <= 4e9` であることを要請する。
+/// 一次関数の集合に対して、点 `x` における最小値・最大値 (`S` で選ぶ) を求めることができる。
 /// 
 /// - 直線追加: amortized `O(log N)` (たぶん)
 /// - max 回答クエリ: `O(log N)`
 /// 
+/// 内部では `S = Min` のとき直線 `(a, b)` を `(-a, -b)` として最大値を求める。
+/// 計算は `i128` で行うので、`|a|, |b|, |x| <= 4e18` かつ答えが `i64` に収まれば安全である。
+/// 
 /// # Reference
 /// 
 /// - [クエリが整数の Convex Hull Trick の凸判定 (noshi91)](https://noshi91.hatenablog.com/entry/2021/03/23/200810)
 ///   + 最大値を求めるため、`max {ax+b >= cx+d}` の形になっている。
-#[derive(Debug)]
-pub struct ConvexHullTrick {
-    pub set: BTreeSet<Line>
+#[derive(Debug, Clone)]
+pub struct ConvexHullTrick<S: Select> {
+    pub set: BTreeSet<Line>,
+    _marker: PhantomData<S>,
 }
 
-impl ConvexHullTrick {
+impl<S: Select> ConvexHullTrick<S> {
     pub fn new() -> Self {
-        Self { set: BTreeSet::from([Line::new(0, -4e18 as i64, i64::MAX)]) }
+        Self { set: BTreeSet::new(), _marker: PhantomData }
+    }
+    
+    pub fn len(&self) -> usize { self.set.len() }
+    pub fn is_empty(&self) -> bool { self.set.is_empty() }
+    
+    /// 外部の直線と内部の直線を変換する。
+    fn conv((a, b): (i64, i64)) -> (i64, i64) {
+        if S::MAX { return (a, b); }
+        assert!(a != i64::MIN && b != i64::MIN, "i64::MIN cannot be negated (@ ConvexHullTrick)");
+        (-a, -b)
     }
     
     pub fn add_line(&mut self, a: i64, b: i64) {
-        assert!(-4e18 as i64 <= a && a <= 4e18 as i64 && -4e18 as i64 <= b && b <= 4e18 as i64);
+        let (a, b) = Self::conv((a, b));
+        if let Some(l) = self.set.get(&a) {
+            if b <= l.b { return; }
+            self.set.remove(&a);
+        }
+        let f = (a, b);
+        let left = |set: &BTreeSet<Line>, a: i64| set.range(..a).next_back().copied();
+        let right = |set: &BTreeSet<Line>, a: i64| set.range((Bound::Excluded(a), Bound::Unbounded)).next().copied();
         
-        let mut del = vec![];
-        let mut l: Option<Line> = None;
-        let mut qr = i64::MAX;
+        // f が最大となる整数の区間 (lo, hi] が空ならば追加しない
+        let lo = left(&self.set, a).map_or(i128::MIN, |l| sep(l.ab(), f));
+        let hi = right(&self.set, a).map_or(i128::MAX, |r| sep(f, r.ab()));
+        if hi <= lo { return; }
         
-        for &lnx in self.set.range(..=a).rev() {
-            if let Some(l) = &mut l {
-                if lnx.r.0 < sep(l.ab(), (a, b)) { break; }
-                del.push(lnx.a);
-                *l = lnx;
-            } else if lnx.a == a {
-                if b <= lnx.b { return; }
-                del.push(lnx.a);
-            } else {
-                if lnx.r.0 < sep(lnx.ab(), (a, b)) { return; }
-                del.push(lnx.a);
-                l = Some(lnx);
-            }
+        while let Some(l) = left(&self.set, a) {
+            let Some(ll) = left(&self.set, l.a) else { break; };
+            if sep(ll.ab(), l.ab()) < sep(l.ab(), f) { break; }
+            self.set.remove(&l.a);
         }
-        
-        for rnx in self.set.range(a+1..) {
-            if sep((a, b), rnx.ab()) < rnx.r.0 { qr = sep((a, b), rnx.ab()); break; }
-            del.push(rnx.a);
+        while let Some(r) = right(&self.set, a) {
+            let Some(rr) = right(&self.set, r.a) else { break; };
+            if sep(f, r.ab()) < sep(r.ab(), rr.ab()) { break; }
+            self.set.remove(&r.a);
         }
         
-        for a in del { self.set.remove(&a); }
-        
-        if let Some(mut l) = l {
-            l.r.0 = sep(l.ab(), (a, b));
+        if let Some(mut l) = left(&self.set, a) {
+            self.set.remove(&l.a);
+            l.r.0 = sep(l.ab(), f);
             self.set.insert(l);
         }
-        
-        self.set.insert(Line::new(a, b, qr));
+        let r = right(&self.set, a).map_or(i128::MAX, |r| sep(f, r.ab()));
+        self.set.insert(Line::new(a, b, r));
+    }
+    
+    /// 点 `x` で最も良い直線 `(a, b)` を返す。直線が存在しないとき `None` を返す。
+    pub fn query_line(&self, x: i64) -> Option<(i64, i64)> {
+        self.set.range(I128(x as i128)..).next().map(|l| Self::conv(l.ab()))
     }
     
+    /// 点 `x` における最小値・最大値を返す。
+    /// 
+    /// # Panics
+    /// 
+    /// if `self` is empty or the answer overflows `i64`.
     pub fn query(&self, x: i64) -> i64 {
-        let (a, b) = self.set.range(I64(x)..).next().unwrap().ab();
-        a*x + b
+        let (a, b) = self.query_line(x).expect("empty (@ ConvexHullTrick::query)");
+        (a as i128 * x as i128 + b as i128).try_into().expect("overflow (@ ConvexHullTrick::query)")
+    }
+    
+    /// `other` の直線を全て `self` に追加する。小さい方を大きい方に追加するので、マージテクに使える。
+    pub fn merge(&mut self, mut other: Self) {
+        if self.set.len() < other.set.len() { std::mem::swap(self, &mut other); }
+        for l in other.set {
+            let (a, b) = Self::conv(l.ab());
+            self.add_line(a, b);
+        }
     }
 }
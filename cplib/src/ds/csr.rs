@@ -1,4 +1,5 @@
 use std::ops::{Index, IndexMut};
+use crate::cplib::traits::grid::Grid;
 
 
 #[derive(Debug)]
@@ -29,6 +30,11 @@ impl Edge {
         Self { dat, idx }
     }
     
+    /// 逆辺のグラフを作る。すなわち、`(i, idx) in csr[j]` means `idx: i -> j`
+    pub fn from_edges_rev(n: usize, iter: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        Self::from_pairs(n, iter.into_iter().enumerate().map(|(k, (i, j))| (j, (i, k))))
+    }
+    
     pub fn sort(&mut self) {
         for i in 0..self.idx_len() {
             self.dat[self.idx[i]..self.idx[i+1]].sort_unstable();
@@ -40,12 +46,87 @@ impl Edge {
     }
 }
 
+/// `(j, w) in csr[i]` means `i -> j` with weight `w`
+impl<W: Default + Clone> CSR<(usize, W)> {
+    /// 無向辺なら `und = true` とすること。
+    pub fn from_weighted_edges(n: usize, und: bool, iter: impl IntoIterator<Item = (usize, usize, W)>) -> Self {
+        let mut v = vec![];
+        for (i, j, w) in iter {
+            if und && i != j { v.push((j, (i, w.clone()))); }
+            v.push((i, (j, w)));
+        }
+        Self::from_pairs(n, v)
+    }
+    
+    /// 全ての辺の向きを反転したグラフを返す。
+    pub fn transpose(&self) -> Self {
+        Self::from_pairs(self.idx_len(), self.iter().flat_map(|(i, r)| r.iter().map(move |(j, w)| (*j, (i, w.clone())))))
+    }
+}
+
+/// `(j, cap, rev) in csr[i]` means `i -> j` with capacity `cap`, and `csr[j][rev]` is its reverse edge
+impl<C: Default + Clone> CSR<(usize, C, usize)> {
+    /// 残余グラフを作る。逆辺の容量は `C::default()` である。
+    /// 
+    /// 辺 `k` は `csr[pos[k].0][pos[k].1]` にあるとして `(csr, pos)` を返す。
+    pub fn from_flow_edges(n: usize, iter: impl IntoIterator<Item = (usize, usize, C)>) -> (Self, Vec<(usize, usize)>) {
+        let e: Vec<_> = iter.into_iter().collect();
+        let mut cnt = vec![0; n];
+        let mut pos = vec![];
+        let mut v = vec![];
+        for (i, j, c) in e {
+            let (pi, pj) = (cnt[i], cnt[j] + (i == j) as usize);
+            cnt[i] += 1; cnt[j] += 1;
+            pos.push((i, pi));
+            v.push((i, (j, c, pj)));
+            v.push((j, (i, C::default(), pi)));
+        }
+        (Self::from_pairs(n, v), pos)
+    }
+}
+
+/// `j in csr[i]` means `i -> j`
+impl CSR<usize> {
+    /// `h*w` グリッドの 4 近傍について、`f(from, to)` を満たすものを辺とするグラフを作る。頂点 `[i, j]` の番号は `i*w + j` である。
+    pub fn from_grid(h: usize, w: usize, f: impl Fn([usize; 2], [usize; 2]) -> bool) -> Self {
+        let mut res = Self::new();
+        for i in 0..h {
+            for j in 0..w {
+                res.next_vec();
+                for [x, y] in [i, j].around4() {
+                    if x < h && y < w && f([i, j], [x, y]) { res.push(x*w + y); }
+                }
+            }
+        }
+        res
+    }
+}
+
 impl<T: Default> CSR<T> {
     /// `[]` に相当する配列を作る。
     pub fn new() -> Self {
         Self { dat: vec![], idx: vec![0] }
     }
     
+    /// `n` 行の配列を作り、`(i, x)` について `csr[i]` に `x` を追加する。各行の中の順序は `iter` の順序である。
+    pub fn from_pairs(n: usize, iter: impl IntoIterator<Item = (usize, T)>) -> Self {
+        let v: Vec<_> = iter.into_iter().collect();
+        let mut idx = vec![0; n+2];
+        for &(i, _) in &v { idx[i+2] += 1; }
+        for i in 0..=n { idx[i+1] += idx[i]; }
+        
+        let mut dat: Vec<T> = (0..v.len()).map(|_| T::default()).collect();
+        for (i, x) in v { dat[idx[i+1]] = x; idx[i+1] += 1; }
+        idx.pop();
+        
+        Self { dat, idx }
+    }
+    
+    /// `(i, &csr[i])` を順に返す。
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &[T])> {
+        (0..self.idx_len()).map(|i| (i, &self[i]))
+    }
+    
     pub fn idx_len(&self) -> usize { self.idx.len()-1 }
     pub fn dat_len(&self) -> usize { self.dat.len() }
     
@@ -0,0 +1,189 @@
+use std::{collections::HashMap, marker::PhantomData, ptr::NonNull};
+use crate::cplib::ds::splay_tree::{SplayOp, Node, NodeRef, merge};
+
+
+/// Euler Tour Tree
+/// 
+/// 頂点 `0..n` からなる森を管理する。各頂点が [`SplayOp::Value`] を持ち、部分木の積・遅延作用ができる。
+/// 
+/// 各木をオイラーツアー (頂点ノードと有向辺ノードの列) として splay 木で管理する。
+/// `Acc` が非可換のとき、`subtree_fold` はツアー順 (行きがけ順) に積を取ったものになる。
+/// 
+/// # 搭載機能
+/// 
+/// - `link, cut, reroot, connected`
+/// - 部分木積・部分木遅延作用 `subtree_fold, subtree_act`
+/// - 一点取得・更新 `get, set`
+pub struct EulerTourTree<Op: SplayOp> {
+    nodes: Vec<Node<Inner<Op>>>,
+    edges: HashMap<(usize, usize), NodeRef<Inner<Op>>>,
+}
+
+impl<Op: SplayOp> EulerTourTree<Op> {
+    /// 各頂点の値が `values` で、辺を持たない森を作成する。
+    pub fn new(values: Vec<Op::Value>) -> Self {
+        Self { nodes: values.into_iter().map(|v| Node::new(Some(v))).collect(), edges: HashMap::new() }
+    }
+    
+    pub fn len(&self) -> usize { self.nodes.len() }
+    
+    fn node(&mut self, i: usize) -> NodeRef<Inner<Op>> {
+        unsafe { NodeRef(NonNull::new_unchecked(self.nodes.as_mut_ptr().add(i))) }
+    }
+    
+    fn edge(&self, u: usize, v: usize) -> NodeRef<Inner<Op>> {
+        *self.edges.get(&(u, v)).expect("edge not found (@ EulerTourTree)")
+    }
+    
+    /// `u` を根とするようにツアーを回転させ、その根を返す。
+    fn rotate_to(&mut self, u: usize) -> NodeRef<Inner<Op>> {
+        let (l, r) = split_before(self.node(u));
+        merge(Some(r), l).unwrap()
+    }
+    
+    /// `u` を根にする。
+    pub fn reroot(&mut self, u: usize) { self.rotate_to(u); }
+    
+    pub fn connected(&mut self, u: usize, v: usize) -> bool {
+        if u == v { return true; }
+        let (x, y) = (self.node(u), self.node(v));
+        splay(x); splay(y);
+        x.parent.is_some()
+    }
+    
+    /// 辺 `(u, v)` を追加する。
+    /// 
+    /// # Panics
+    /// 
+    /// if `u` and `v` are already connected.
+    pub fn link(&mut self, u: usize, v: usize) {
+        assert!(!self.connected(u, v), "already connected (@ EulerTourTree::link)");
+        let new = || unsafe { NodeRef(NonNull::new_unchecked(Box::into_raw(Box::new(Node::new(None))))) };
+        let (uv, vu) = (new(), new());
+        self.edges.insert((u, v), uv);
+        self.edges.insert((v, u), vu);
+        let tu = self.rotate_to(u);
+        let tv = self.rotate_to(v);
+        merge(merge(merge(Some(tu), Some(uv)), Some(tv)), Some(vu));
+    }
+    
+    /// 辺 `(u, v)` を削除する。
+    /// 
+    /// # Panics
+    /// 
+    /// if edge `(u, v)` does not exist.
+    pub fn cut(&mut self, u: usize, v: usize) {
+        let (uv, vu) = (self.edge(u, v), self.edge(v, u));
+        // [a, uv, x, vu, c] -> [a, c], [x]
+        self.rotate_to(u);
+        let (a, _) = split_before(uv);
+        split_after(uv);
+        let (_, c) = split_after(vu);
+        split_before(vu);
+        merge(a, c);
+        self.edges.remove(&(u, v));
+        self.edges.remove(&(v, u));
+        unsafe {
+            drop(Box::from_raw(uv.0.as_ptr()));
+            drop(Box::from_raw(vu.0.as_ptr()));
+        }
+    }
+    
+    /// 辺 `(p, v)` を切ったときの `v` 側の部分木を切り出して `f` を呼び、元に戻す。
+    fn with_subtree<T>(&mut self, v: usize, p: usize, f: impl FnOnce(NodeRef<Inner<Op>>) -> T) -> T {
+        let (pv, vp) = (self.edge(p, v), self.edge(v, p));
+        // [l, pv, x, vp, r]
+        self.rotate_to(p);
+        let (l, _) = split_after(pv);
+        let (_, r) = split_before(vp);
+        let x = self.node(v);
+        splay(x);
+        let res = f(x);
+        merge(merge(Some(l), Some(x)), Some(r));
+        res
+    }
+    
+    /// `v` の親を `p` としたときの `v` の部分木の積を返す。
+    /// 
+    /// # Panics
+    /// 
+    /// if edge `(p, v)` does not exist.
+    pub fn subtree_fold(&mut self, v: usize, p: usize) -> Op::Acc {
+        self.with_subtree(v, p, |x| x.acc[0].clone().unwrap())
+    }
+    
+    /// `v` の親を `p` としたときの `v` の部分木に `lazy` を作用させる。
+    /// 
+    /// # Panics
+    /// 
+    /// if edge `(p, v)` does not exist.
+    pub fn subtree_act(&mut self, v: usize, p: usize, lazy: &Op::Lazy) {
+        self.with_subtree(v, p, |x| x.apply(lazy));
+    }
+    
+    pub fn get(&mut self, i: usize) -> &Op::Value {
+        self.node(i).push_path();
+        self.nodes[i].value.as_ref().unwrap()
+    }
+    
+    pub fn set<T>(&mut self, i: usize, f: impl FnOnce(&mut Op::Value) -> T) -> T {
+        let mut x = self.node(i);
+        splay(x);
+        let res = f(x.value.as_mut().unwrap());
+        x.recalc();
+        res
+    }
+}
+
+impl<Op: SplayOp> Drop for EulerTourTree<Op> {
+    fn drop(&mut self) {
+        for (_, p) in self.edges.drain() { unsafe { drop(Box::from_raw(p.0.as_ptr())); } }
+    }
+}
+
+
+
+/// 辺ノードの値を `None` とするための内部演算
+struct Inner<Op>(PhantomData<Op>);
+
+impl<Op: SplayOp> SplayOp for Inner<Op> {
+    type Value = Option<Op::Value>;
+    type Acc = Option<Op::Acc>;
+    type Lazy = Op::Lazy;
+    
+    fn to_acc(value: &Self::Value) -> Self::Acc { value.as_ref().map(Op::to_acc) }
+    fn prod_acc(lhs: &Self::Acc, rhs: &Self::Acc) -> Self::Acc {
+        match (lhs, rhs) {
+            (Some(l), Some(r)) => Some(Op::prod_acc(l, r)),
+            (l, None) => l.clone(),
+            (None, r) => r.clone()
+        }
+    }
+    fn act_value(value: &mut Self::Value, lazy: &Self::Lazy) {
+        if let Some(v) = value { Op::act_value(v, lazy); }
+    }
+    fn act_acc(acc: &mut Self::Acc, lazy: &Self::Lazy) {
+        if let Some(a) = acc { Op::act_acc(a, lazy); }
+    }
+    fn comp_lazy(lazy: &mut Self::Lazy, ad: &Self::Lazy) { Op::comp_lazy(lazy, ad); }
+}
+
+fn splay<Op: SplayOp>(x: NodeRef<Op>) { x.push_path(); x.splay(); }
+
+/// `x` を含む列を、`x` の直前で分割する。
+fn split_before<Op: SplayOp>(mut x: NodeRef<Op>) -> (Option<NodeRef<Op>>, NodeRef<Op>) {
+    splay(x);
+    let l = x.child[0].take();
+    if let Some(mut l) = l { l.parent = None; }
+    x.recalc();
+    (l, x)
+}
+
+/// `x` を含む列を、`x` の直後で分割する。
+fn split_after<Op: SplayOp>(mut x: NodeRef<Op>) -> (NodeRef<Op>, Option<NodeRef<Op>>) {
+    splay(x);
+    let r = x.child[1].take();
+    if let Some(mut r) = r { r.parent = None; }
+    x.recalc();
+    (x, r)
+}
@@ -0,0 +1,154 @@
+//! Fenwick 木 (Binary Indexed Tree)
+
+use std::ops::RangeBounds;
+use crate::cplib::{ds::unionfind::Abelian, util::func::to_bounds};
+
+
+
+/// Fenwick 木 (Binary Indexed Tree)
+/// 
+/// 可換群 [`Abelian`] の列に対して、1 点加算と区間和を `O(log N)` で計算する。
+/// 
+/// # 搭載機能
+/// 
+/// - `Clone`, `Debug`, `FromIterator<Op::T>`
+pub struct Fenwick<Op: Abelian> {
+    /// 1-indexed
+    dat: Vec<Op::T>,
+}
+
+impl<Op: Abelian> Fenwick<Op> {
+    pub fn new(len: usize) -> Self {
+        Self { dat: vec![Op::e(); len+1] }
+    }
+    
+    pub fn len(&self) -> usize { self.dat.len()-1 }
+    
+    /// `A[i] += w`
+    pub fn add(&mut self, i: usize, w: &Op::T) {
+        assert!(i < self.len());
+        let mut i = i+1;
+        while i < self.dat.len() {
+            self.dat[i] = Op::add(&self.dat[i], w);
+            i += i & i.wrapping_neg();
+        }
+    }
+    
+    /// `A[i] = w`
+    pub fn set(&mut self, i: usize, w: &Op::T) {
+        let d = Op::sub(w, &self.get(i));
+        self.add(i, &d);
+    }
+    
+    pub fn get(&self, i: usize) -> Op::T {
+        assert!(i < self.len());
+        self.fold(i..=i)
+    }
+    
+    /// `sum(A[..r])` を返す。
+    pub fn sum(&self, mut r: usize) -> Op::T {
+        assert!(r <= self.len());
+        let mut res = Op::e();
+        while r != 0 { res = Op::add(&res, &self.dat[r]); r &= r-1; }
+        res
+    }
+    
+    pub fn fold(&self, range: impl RangeBounds<usize>) -> Op::T {
+        let [l, r] = to_bounds(range, self.len());
+        Op::sub(&self.sum(r), &self.sum(l))
+    }
+    
+    /// `f(sum(A[..r])) == true && f(sum(A[..r+1])) == false` である `r` を一つ返す。
+    /// ただし `f(e) == true`, `f(sum(A[..len+1])) == false` とし、`f` は単調であるとする。
+    pub fn max_right(&self, f: impl Fn(&Op::T) -> bool) -> usize {
+        let (mut r, mut acc) = (0, Op::e());
+        if self.len() == 0 { return 0; }
+        let mut k = 1 << self.len().ilog2();
+        while k != 0 {
+            if r+k <= self.len() {
+                let tmp = Op::add(&acc, &self.dat[r+k]);
+                if f(&tmp) { r += k; acc = tmp; }
+            }
+            k >>= 1;
+        }
+        r
+    }
+    
+    /// `sum(A[..r]) < w <= sum(A[..r+1])` である `r` を返す。`A` は非負であるとする。
+    /// 
+    /// 個数を管理しているとき、`lower_bound(k+1)` は `k` 番目 (0-indexed) の要素の位置である。
+    pub fn lower_bound(&self, w: &Op::T) -> usize where Op::T: Ord {
+        self.max_right(|s| s < w)
+    }
+}
+
+impl<Op: Abelian> FromIterator<Op::T> for Fenwick<Op> {
+    fn from_iter<I: IntoIterator<Item = Op::T>>(iter: I) -> Self {
+        let mut dat = vec![Op::e()];
+        dat.extend(iter);
+        for i in 1..dat.len() {
+            let j = i + (i & i.wrapping_neg());
+            if j < dat.len() { dat[j] = Op::add(&dat[j], &dat[i]); }
+        }
+        Self { dat }
+    }
+}
+
+impl<Op: Abelian> Clone for Fenwick<Op> {
+    fn clone(&self) -> Self { Self { dat: self.dat.clone() } }
+}
+
+impl<Op: Abelian> std::fmt::Debug for Fenwick<Op> where Op::T: std::fmt::Debug {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", (0..self.len()).map(|i| self.get(i)).collect::<Vec<_>>())
+    }
+}
+
+
+
+/// 区間加算・区間和の Fenwick 木
+/// 
+/// `sum(A[..r]) = B0[..r] + r * B1[..r]` となるよう 2 本の [`Fenwick`] を管理する。
+pub struct RangeFenwick<Op: Abelian> {
+    b0: Fenwick<Op>,
+    b1: Fenwick<Op>,
+}
+
+impl<Op: Abelian> RangeFenwick<Op> {
+    pub fn new(len: usize) -> Self {
+        Self { b0: Fenwick::new(len), b1: Fenwick::new(len) }
+    }
+    
+    pub fn len(&self) -> usize { self.b0.len() }
+    
+    /// `A[range] += w`
+    pub fn add(&mut self, range: impl RangeBounds<usize>, w: &Op::T) {
+        let [l, r] = to_bounds(range, self.len());
+        if l == r { return; }
+        self.b0.add(l, &Op::inv(&Op::mul(w, l)));
+        self.b1.add(l, w);
+        if r < self.len() {
+            self.b0.add(r, &Op::mul(w, r));
+            self.b1.add(r, &Op::inv(w));
+        }
+    }
+    
+    /// `sum(A[..r])` を返す。
+    pub fn sum(&self, r: usize) -> Op::T {
+        Op::add(&self.b0.sum(r), &Op::mul(&self.b1.sum(r), r))
+    }
+    
+    pub fn fold(&self, range: impl RangeBounds<usize>) -> Op::T {
+        let [l, r] = to_bounds(range, self.len());
+        Op::sub(&self.sum(r), &self.sum(l))
+    }
+    
+    pub fn get(&self, i: usize) -> Op::T {
+        assert!(i < self.len());
+        self.fold(i..=i)
+    }
+}
+
+impl<Op: Abelian> Clone for RangeFenwick<Op> {
+    fn clone(&self) -> Self { Self { b0: self.b0.clone(), b1: self.b1.clone() } }
+}
@@ -0,0 +1,91 @@
+//! 点の座標を先読みする 2 次元 Fenwick 木
+
+use std::ops::Range;
+use crate::cplib::ds::{unionfind::Abelian, fenwick::Fenwick};
+
+
+
+/// 点の座標を先読みする 2 次元 Fenwick 木
+/// 
+/// `x` 座標のセグメント木の各ノードに、そのノードに含まれる点の `y` 座標で圧縮した Fenwick 木を持たせている。
+/// 
+/// - 構築: `O(N log N)`
+/// - `add`, `set`, `fold`: `O(log^2 N)`
+/// 
+/// # Panics
+/// 
+/// 構築時に与えなかった点を更新しようとしたとき。
+pub struct Fenwick2D<Op: Abelian> {
+    /// 圧縮後の `x` 座標
+    xs: Vec<i64>,
+    /// 点 `(x, y)` の辞書順
+    pts: Vec<(i64, i64)>,
+    val: Vec<Op::T>,
+    /// `ys[node]`: `node` に含まれる点の `y` 座標 (昇順)
+    ys: Vec<Vec<i64>>,
+    /// `bit[node]`: `ys[node]` 上の Fenwick 木
+    bit: Vec<Fenwick<Op>>,
+}
+
+impl<Op: Abelian> Fenwick2D<Op> {
+    pub fn new(points: impl IntoIterator<Item = (i64, i64)>) -> Self {
+        let mut pts: Vec<_> = points.into_iter().collect();
+        pts.sort_unstable(); pts.dedup();
+        let mut xs: Vec<_> = pts.iter().map(|p| p.0).collect();
+        xs.dedup();
+        let n = xs.len();
+        let mut ys = vec![vec![]; 2*n];
+        let mut xi = 0;
+        for &(x, y) in &pts {
+            while xs[xi] != x { xi += 1; }
+            let mut i = n+xi;
+            while i != 0 { ys[i].push(y); i >>= 1; }
+        }
+        for y in &mut ys { y.sort_unstable(); y.dedup(); }
+        let bit = ys.iter().map(|y| Fenwick::new(y.len())).collect();
+        Self { val: vec![Op::e(); pts.len()], xs, pts, ys, bit }
+    }
+    
+    /// 点 `(x, y)` の値を返す。
+    pub fn get(&self, x: i64, y: i64) -> &Op::T {
+        &self.val[self.pts.binary_search(&(x, y)).unwrap()]
+    }
+    
+    /// 点 `(x, y)` に `w` を加える。
+    pub fn add(&mut self, x: i64, y: i64, w: &Op::T) {
+        let k = self.pts.binary_search(&(x, y)).unwrap();
+        self.val[k] = Op::add(&self.val[k], w);
+        let mut i = self.xs.len() + self.xs.binary_search(&x).unwrap();
+        while i != 0 {
+            let j = self.ys[i].binary_search(&y).unwrap();
+            self.bit[i].add(j, w);
+            i >>= 1;
+        }
+    }
+    
+    /// 点 `(x, y)` の値を `w` にする。
+    pub fn set(&mut self, x: i64, y: i64, w: &Op::T) {
+        let d = Op::sub(w, self.get(x, y));
+        self.add(x, y, &d);
+    }
+    
+    /// `[x1, x2) × [y1, y2)` に含まれる点の値の和を返す。
+    pub fn fold(&self, x: Range<i64>, y: Range<i64>) -> Op::T {
+        let n = self.xs.len();
+        let mut l = n + self.xs.partition_point(|&v| v < x.start);
+        let mut r = n + self.xs.partition_point(|&v| v < x.end);
+        let mut res = Op::e();
+        while l < r {
+            if l&1 == 1 { res = Op::add(&res, &self.fold_node(l, &y)); l += 1; }
+            if r&1 == 1 { r -= 1; res = Op::add(&res, &self.fold_node(r, &y)); }
+            l >>= 1; r >>= 1;
+        }
+        res
+    }
+    
+    fn fold_node(&self, i: usize, y: &Range<i64>) -> Op::T {
+        let l = self.ys[i].partition_point(|&v| v < y.start);
+        let r = self.ys[i].partition_point(|&v| v < y.end);
+        self.bit[i].fold(l..r)
+    }
+}
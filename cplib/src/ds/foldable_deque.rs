@@ -1,4 +1,5 @@
-use std::{collections::VecDeque, fmt::Debug};
+use std::{collections::{VecDeque, vec_deque}, fmt::Debug, iter::{Chain, Map, Rev}, ops::RangeBounds, slice};
+use crate::cplib::util::func::to_bounds;
 
 
 
@@ -14,6 +15,10 @@ pub trait FoldableDequeOp {
 
 /// - `push, pop`: amortized `O(1)`
 /// - `fold`: `O(1)`
+/// - `fold_range`: 前回の `fold_range` 以降の push 回数を `k` として amortized `O(k log N + log N)`
+/// 
+/// `fold_range` のために、リングバッファ上のセグメント木を遅延して構築・更新する。
+/// `fold_range` を使わなければ、その分のコストはかからない。
 /// 
 /// # Implemented Traits
 /// 
@@ -22,16 +27,30 @@ pub struct FoldableDeque<Op: FoldableDequeOp> {
     deque: VecDeque<Op::Value>,
     front: Vec<Op::Acc>,
     back: Vec<Op::Acc>,
-    e: Op::Acc
+    e: Op::Acc,
+    /// リングバッファ上のセグメント木。長さは `2*cap` で、空のときは未構築を表す。
+    seg: Vec<Op::Acc>,
+    /// 先頭の要素のリングバッファ上の位置
+    head: usize,
+    /// セグメント木に未反映の位置
+    dirty: Vec<usize>,
 }
 
 impl<Op: FoldableDequeOp> FoldableDeque<Op> {
     pub fn new() -> Self {
-        Self { deque: VecDeque::new(), front: vec![], back: vec![], e: Op::id_acc() }
+        Self { deque: VecDeque::new(), front: vec![], back: vec![], e: Op::id_acc(), seg: vec![], head: 0, dirty: vec![] }
     }
     
     pub fn deque(&self) -> &VecDeque<Op::Value> { &self.deque }
     pub fn len(&self) -> usize { self.deque.len() }
+    pub fn is_empty(&self) -> bool { self.deque.is_empty() }
+    pub fn get(&self, idx: usize) -> Option<&Op::Value> { self.deque.get(idx) }
+    pub fn iter(&self) -> vec_deque::Iter<'_, Op::Value> { self.deque.iter() }
+    
+    pub fn clear(&mut self) {
+        self.deque.clear(); self.front.clear(); self.back.clear();
+        self.seg.clear(); self.dirty.clear(); self.head = 0;
+    }
     
     fn fold_front(&self) -> &Op::Acc { self.front.last().unwrap_or(&self.e) }
     fn fold_back(&self) -> &Op::Acc { self.back.last().unwrap_or(&self.e) }
@@ -40,16 +59,25 @@ impl<Op: FoldableDequeOp> FoldableDeque<Op> {
     pub fn push_front(&mut self, v: Op::Value) {
         self.front.push(Op::prod_acc(&Op::to_acc(&v), self.fold_front()));
         self.deque.push_front(v);
+        if !self.seg.is_empty() {
+            self.head = self.head.wrapping_sub(1) & self.cap()-1;
+            self.mark(self.head);
+        }
     }
     
     pub fn push_back(&mut self, v: Op::Value) {
         self.back.push(Op::prod_acc(self.fold_back(), &Op::to_acc(&v)));
         self.deque.push_back(v);
+        if !self.seg.is_empty() {
+            let pos = self.head + self.len()-1 & self.cap()-1;
+            self.mark(pos);
+        }
     }
     
     pub fn pop_front(&mut self) -> Option<Op::Value> {
         let res = self.deque.pop_front();
         if self.front.pop().is_none() { self.rebuild(); }
+        if res.is_some() && !self.seg.is_empty() { self.head = self.head+1 & self.cap()-1; }
         res
     }
     
@@ -67,6 +95,64 @@ impl<Op: FoldableDequeOp> FoldableDeque<Op> {
         tmp = Op::id_acc();
         for i in len/2..len { tmp = Op::prod_acc(&tmp, &Op::to_acc(&self.deque[i])); self.back.push(tmp.clone()); }
     }
+    
+    
+    
+    fn cap(&self) -> usize { self.seg.len() / 2 }
+    
+    /// 位置 `pos` を未反映とする。容量が足りないか未反映の位置が多すぎるときは、セグメント木を破棄する。
+    fn mark(&mut self, pos: usize) {
+        if self.len() >= self.cap() || self.dirty.len() >= self.cap() {
+            self.seg.clear(); self.dirty.clear();
+        } else {
+            self.dirty.push(pos);
+        }
+    }
+    
+    /// セグメント木を最新の状態にする。
+    fn flush(&mut self) {
+        if self.seg.is_empty() {
+            let cap = (self.len()*2).next_power_of_two().max(2);
+            self.seg = vec![Op::id_acc(); cap*2];
+            for (i, v) in self.deque.iter().enumerate() { self.seg[cap+i] = Op::to_acc(v); }
+            for i in (1..cap).rev() { self.seg[i] = Op::prod_acc(&self.seg[i*2], &self.seg[i*2+1]); }
+            self.head = 0;
+            return;
+        }
+        let cap = self.cap();
+        for pos in std::mem::take(&mut self.dirty) {
+            let i = pos.wrapping_sub(self.head) & cap-1;
+            let mut p = cap + pos;
+            self.seg[p] = if i < self.len() { Op::to_acc(&self.deque[i]) } else { Op::id_acc() };
+            while p > 1 { p >>= 1; self.seg[p] = Op::prod_acc(&self.seg[p*2], &self.seg[p*2+1]); }
+        }
+    }
+    
+    /// リングバッファ上の位置 `[l, r)` の積を返す。
+    fn prod_seg(&self, l: usize, r: usize) -> Op::Acc {
+        let (mut l, mut r) = (l + self.cap(), r + self.cap());
+        let (mut rl, mut rr) = (Op::id_acc(), Op::id_acc());
+        while l < r {
+            if l&1 == 1 { rl = Op::prod_acc(&rl, &self.seg[l]); l += 1; }
+            if r&1 == 1 { r -= 1; rr = Op::prod_acc(&self.seg[r], &rr); }
+            l >>= 1; r >>= 1;
+        }
+        Op::prod_acc(&rl, &rr)
+    }
+    
+    /// 先頭から数えて `range` の範囲にある要素の積を返す。
+    pub fn fold_range(&mut self, range: impl RangeBounds<usize>) -> Op::Acc {
+        let [l, r] = to_bounds(range, self.len());
+        if l == r { return Op::id_acc(); }
+        self.flush();
+        let mask = self.cap()-1;
+        let (l, r) = (self.head + l & mask, self.head + r & mask);
+        if l < r {
+            self.prod_seg(l, r)
+        } else {
+            Op::prod_acc(&self.prod_seg(l, self.cap()), &self.prod_seg(0, r))
+        }
+    }
 }
 
 impl<Op: FoldableDequeOp> FromIterator<Op::Value> for FoldableDeque<Op> {
@@ -79,7 +165,10 @@ impl<Op: FoldableDequeOp> FromIterator<Op::Value> for FoldableDeque<Op> {
 
 impl<Op: FoldableDequeOp> Clone for FoldableDeque<Op> where Op::Value: Clone {
     fn clone(&self) -> Self {
-        Self { deque: self.deque.clone(), front: self.front.clone(), back: self.back.clone(), e: self.e.clone() }
+        Self {
+            deque: self.deque.clone(), front: self.front.clone(), back: self.back.clone(), e: self.e.clone(),
+            seg: self.seg.clone(), head: self.head, dirty: self.dirty.clone()
+        }
     }
 }
 
@@ -88,3 +177,91 @@ impl<Op: FoldableDequeOp> Debug for FoldableDeque<Op> where Op::Value: Debug, Op
         write!(f, "{:?} (fold: {:?})", self.deque, self.fold())
     }
 }
+
+
+
+/// 末尾への追加と先頭の削除のみができる [`FoldableDeque`]。値を二重に持たないので、その分高速である。
+/// 
+/// - `push, pop`: amortized `O(1)`
+/// - `fold`: `O(1)`
+/// 
+/// # Implemented Traits
+/// 
+/// - `Clone`, `Debug`, `FromIterator<T>`
+pub struct FoldableQueue<Op: FoldableDequeOp> {
+    /// 先頭側の要素とそこから先頭までの積。末尾が先頭の要素である。
+    front: Vec<(Op::Value, Op::Acc)>,
+    back: Vec<Op::Value>,
+    back_acc: Op::Acc,
+    e: Op::Acc
+}
+
+impl<Op: FoldableDequeOp> FoldableQueue<Op> {
+    pub fn new() -> Self {
+        Self { front: vec![], back: vec![], back_acc: Op::id_acc(), e: Op::id_acc() }
+    }
+    
+    pub fn len(&self) -> usize { self.front.len() + self.back.len() }
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+    
+    pub fn get(&self, idx: usize) -> Option<&Op::Value> {
+        if idx < self.front.len() { Some(&self.front[self.front.len()-1-idx].0) } else { self.back.get(idx-self.front.len()) }
+    }
+    
+    pub fn front(&self) -> Option<&Op::Value> { self.get(0) }
+    
+    pub fn iter(&self) -> QueueIter<'_, Op> {
+        self.front.iter().rev().map((|p| &p.0) as fn(&(Op::Value, Op::Acc)) -> &Op::Value).chain(self.back.iter())
+    }
+    
+    pub fn clear(&mut self) {
+        self.front.clear(); self.back.clear(); self.back_acc = Op::id_acc();
+    }
+    
+    pub fn fold(&self) -> Op::Acc {
+        Op::prod_acc(self.front.last().map_or(&self.e, |p| &p.1), &self.back_acc)
+    }
+    
+    pub fn push(&mut self, v: Op::Value) {
+        self.back_acc = Op::prod_acc(&self.back_acc, &Op::to_acc(&v));
+        self.back.push(v);
+    }
+    
+    pub fn pop(&mut self) -> Option<Op::Value> {
+        if self.front.is_empty() {
+            let mut tmp = Op::id_acc();
+            for v in self.back.drain(..).rev() {
+                tmp = Op::prod_acc(&Op::to_acc(&v), &tmp);
+                self.front.push((v, tmp.clone()));
+            }
+            self.back_acc = Op::id_acc();
+        }
+        self.front.pop().map(|p| p.0)
+    }
+}
+
+pub type QueueIter<'a, Op> = Chain<
+    Map<Rev<slice::Iter<'a, (<Op as FoldableDequeOp>::Value, <Op as FoldableDequeOp>::Acc)>>, fn(&(<Op as FoldableDequeOp>::Value, <Op as FoldableDequeOp>::Acc)) -> &<Op as FoldableDequeOp>::Value>,
+    slice::Iter<'a, <Op as FoldableDequeOp>::Value>
+>;
+
+impl<Op: FoldableDequeOp> FromIterator<Op::Value> for FoldableQueue<Op> {
+    fn from_iter<T: IntoIterator<Item = Op::Value>>(iter: T) -> Self {
+        let mut que = Self::new();
+        for v in iter { que.push(v); }
+        que
+    }
+}
+
+impl<Op: FoldableDequeOp> Clone for FoldableQueue<Op> where Op::Value: Clone {
+    fn clone(&self) -> Self {
+        Self { front: self.front.clone(), back: self.back.clone(), back_acc: self.back_acc.clone(), e: self.e.clone() }
+    }
+}
+
+impl<Op: FoldableDequeOp> Debug for FoldableQueue<Op> where Op::Value: Debug, Op::Acc: Debug {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()?;
+        write!(f, " (fold: {:?})", self.fold())
+    }
+}
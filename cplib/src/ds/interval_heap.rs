@@ -0,0 +1,98 @@
+use std::fmt::Debug;
+
+
+/// 両端優先度付きキュー (interval heap)
+/// 
+/// 最小値・最大値の取得が `O(1)`, 挿入・削除が `O(log N)` である。
+/// 
+/// `v[2k], v[2k+1]` がノード `k` の区間 `[min, max]` をなし、親ノードの区間は子ノードの区間を含む。
+#[derive(Clone)]
+pub struct IntervalHeap<T: Ord> {
+    v: Vec<T>,
+}
+
+impl<T: Ord> IntervalHeap<T> {
+    pub fn new() -> Self { Self { v: vec![] } }
+    pub fn len(&self) -> usize { self.v.len() }
+    pub fn is_empty(&self) -> bool { self.v.is_empty() }
+    pub fn clear(&mut self) { self.v.clear(); }
+    
+    pub fn min(&self) -> Option<&T> { self.v.first() }
+    pub fn max(&self) -> Option<&T> { self.v.get(1).or(self.v.first()) }
+    
+    pub fn push(&mut self, x: T) {
+        self.v.push(x);
+        self.up(self.v.len()-1);
+    }
+    
+    pub fn pop_min(&mut self) -> Option<T> {
+        if self.v.len() <= 2 { return (!self.v.is_empty()).then(|| self.v.swap_remove(0)); }
+        let res = self.v.swap_remove(0);
+        self.down_min();
+        Some(res)
+    }
+    
+    pub fn pop_max(&mut self) -> Option<T> {
+        if self.v.len() <= 2 { return self.v.pop(); }
+        let res = self.v.swap_remove(1);
+        self.down_max();
+        Some(res)
+    }
+    
+    /// 要素を昇順に並べた `Vec` を返す。
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        self.v.sort();
+        self.v
+    }
+    
+    fn up(&mut self, mut i: usize) {
+        let v = &mut self.v;
+        if i & 1 == 1 && v[i-1] > v[i] { v.swap(i-1, i); i -= 1; }
+        if i < 2 { return; }
+        let p = (i/2-1)/2;
+        let dir = if v[i] < v[2*p] { 0 } else if v[i] > v[2*p+1] { 1 } else { return; };
+        while i >= 2 {
+            let j = 2*((i/2-1)/2) + dir;
+            if (dir == 0 && v[i] < v[j]) || (dir == 1 && v[i] > v[j]) { v.swap(i, j); i = j; } else { break; }
+        }
+    }
+    
+    fn down_min(&mut self) {
+        let n = self.v.len();
+        let v = &mut self.v;
+        let mut i = 0;
+        loop {
+            if i+1 < n && v[i] > v[i+1] { v.swap(i, i+1); }
+            let c = 2*(i/2)+1;
+            let Some(m) = [2*c, 2*c+2].into_iter().filter(|&j| j < n).min_by(|&a, &b| v[a].cmp(&v[b])) else { break; };
+            if v[m] < v[i] { v.swap(i, m); i = m; } else { break; }
+        }
+    }
+    
+    fn down_max(&mut self) {
+        let n = self.v.len();
+        let v = &mut self.v;
+        let mut i = 1;
+        loop {
+            if v[i-1] > v[i] { v.swap(i-1, i); }
+            let c = 2*(i/2)+1;
+            let Some(m) = [2*c, 2*c+2].into_iter().filter(|&j| j < n).map(|j| if j+1 < n { j+1 } else { j }).max_by(|&a, &b| v[a].cmp(&v[b])) else { break; };
+            if v[m] > v[i] { v.swap(i, m); i = m; } else { break; }
+            if i & 1 == 0 { break; }
+        }
+    }
+}
+
+impl<T: Ord> FromIterator<T> for IntervalHeap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut res = Self::new();
+        for x in iter { res.push(x); }
+        res
+    }
+}
+
+impl<T: Ord + Debug> Debug for IntervalHeap<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.v.iter()).finish()
+    }
+}
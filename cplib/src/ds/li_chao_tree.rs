@@ -0,0 +1,264 @@
+use std::{marker::PhantomData, ops::{Add, Mul, RangeBounds, Bound}};
+use crate::cplib::traits::select::Select;
+
+
+/// [`LiChaoTree`] の係数・座標に使える数値型
+pub trait LineNum: Copy + PartialOrd + Add<Output = Self> + Mul<Output = Self> {
+    fn from_i64(x: i64) -> Self;
+}
+
+macro_rules! impl_line_num {
+    ($($t:ty),*) => { $( impl LineNum for $t { fn from_i64(x: i64) -> Self { x as $t } } )* };
+}
+impl_line_num!(i64, i128, f64);
+
+fn eval<T: LineNum>((a, b): (T, T), x: T) -> T { a*x + b }
+
+
+
+/// Li Chao Tree
+/// 
+/// 直線・線分 `y = ax + b` を追加し、先読みした点 `x` における最小値・最大値 (`S` で選ぶ) を求める。
+/// 
+/// - `add_line`: `O(log N)`, `add_segment`: `O(log^2 N)`, `query`: `O(log N)`
+/// - `snapshot, rollback` で追加を取り消せる。木 DP などで使う。
+///   最初の `snapshot` 以降は変更履歴を記録し続けるので、不要になったら `commit` で破棄する。
+/// 
+/// 座標が先読みできないときは [`DynamicLiChaoTree`] を使う。
+pub struct LiChaoTree<T: LineNum, S: Select> {
+    xs: Vec<T>,
+    size: usize,
+    dat: Vec<Option<(T, T)>>,
+    history: Vec<(usize, Option<(T, T)>)>,
+    /// `snapshot` されてから `commit` されるまで `true`
+    record: bool,
+    _marker: PhantomData<S>,
+}
+
+impl<T: LineNum, S: Select> LiChaoTree<T, S> {
+    /// クエリの点を `xs` とする。
+    pub fn new(mut xs: Vec<T>) -> Self {
+        xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        xs.dedup_by(|a, b| a == b);
+        assert!(!xs.is_empty());
+        let size = xs.len().next_power_of_two();
+        Self { xs, size, dat: vec![None; 2*size], history: vec![], record: false, _marker: PhantomData }
+    }
+    
+    fn x(&self, i: usize) -> T { self.xs[i.min(self.xs.len()-1)] }
+    
+    fn assign(&mut self, k: usize, line: Option<(T, T)>) {
+        let old = std::mem::replace(&mut self.dat[k], line);
+        if self.record { self.history.push((k, old)); }
+    }
+    
+    /// ノード `k` (区間 `[l, r)`) 以下に直線 `f` を追加する。
+    fn insert(&mut self, mut k: usize, mut l: usize, mut r: usize, mut f: (T, T)) {
+        loop {
+            let Some(g) = self.dat[k] else { self.assign(k, Some(f)); return; };
+            let m = (l+r)/2;
+            if S::better(&eval(f, self.x(m)), &eval(g, self.x(m))) {
+                self.assign(k, Some(f));
+                f = g;
+            }
+            if r-l == 1 { return; }
+            let g = self.dat[k].unwrap();
+            if S::better(&eval(f, self.x(l)), &eval(g, self.x(l))) {
+                (k, r) = (2*k, m);
+            } else if S::better(&eval(f, self.x(r-1)), &eval(g, self.x(r-1))) {
+                (k, l) = (2*k+1, m);
+            } else {
+                return;
+            }
+        }
+    }
+    
+    /// 直線 `y = ax + b` を追加する。
+    pub fn add_line(&mut self, a: T, b: T) {
+        self.insert(1, 0, self.size, (a, b));
+    }
+    
+    /// `x in range` の範囲のみに存在する線分 `y = ax + b` を追加する。
+    pub fn add_segment(&mut self, a: T, b: T, range: impl RangeBounds<T>) {
+        let mut l = match range.start_bound() {
+            Bound::Included(x) => self.xs.partition_point(|y| y < x),
+            Bound::Excluded(x) => self.xs.partition_point(|y| y <= x),
+            Bound::Unbounded => 0
+        } + self.size;
+        let mut r = match range.end_bound() {
+            Bound::Included(x) => self.xs.partition_point(|y| y <= x),
+            Bound::Excluded(x) => self.xs.partition_point(|y| y < x),
+            Bound::Unbounded => self.xs.len()
+        } + self.size;
+        let mut w = 1;
+        while l < r {
+            if l & 1 == 1 { let s = l*w - self.size; self.insert(l, s, s+w, (a, b)); l += 1; }
+            if r & 1 == 1 { r -= 1; let s = r*w - self.size; self.insert(r, s, s+w, (a, b)); }
+            l >>= 1; r >>= 1; w <<= 1;
+        }
+    }
+    
+    /// 点 `x` で最も良い直線 `(a, b)` を返す。直線が存在しないとき `None` を返す。
+    /// 
+    /// # Panics
+    /// 
+    /// if `x` is not in `xs`.
+    pub fn query_line(&self, x: T) -> Option<(T, T)> {
+        let i = self.xs.partition_point(|y| *y < x);
+        assert!(i < self.xs.len() && self.xs[i] == x, "x is not in xs (@ LiChaoTree::query_line)");
+        let mut k = i + self.size;
+        let mut res: Option<(T, T)> = None;
+        while k != 0 {
+            if let Some(f) = self.dat[k] {
+                if res.map_or(true, |g| S::better(&eval(f, x), &eval(g, x))) { res = Some(f); }
+            }
+            k >>= 1;
+        }
+        res
+    }
+    
+    /// 点 `x` における最小値・最大値を返す。直線が存在しないとき `None` を返す。
+    pub fn query(&self, x: T) -> Option<T> { self.query_line(x).map(|f| eval(f, x)) }
+    
+    /// 現在の状態を表す値を返す。[`LiChaoTree::rollback`] に渡すと、この状態に戻る。
+    /// 
+    /// これ以降 [`LiChaoTree::commit`] を呼ぶまで、追加のたびに変更履歴を記録する。
+    pub fn snapshot(&mut self) -> usize { self.record = true; self.history.len() }
+    
+    /// 変更履歴を破棄し、記録をやめる。それまでの `snapshot` の値は使えなくなる。
+    pub fn commit(&mut self) { self.history.clear(); self.record = false; }
+    
+    pub fn rollback(&mut self, snapshot: usize) {
+        while self.history.len() > snapshot {
+            let (k, f) = self.history.pop().unwrap();
+            self.dat[k] = f;
+        }
+    }
+}
+
+
+
+/// 動的 Li Chao Tree
+/// 
+/// 整数の座標 `[lo, hi)` 上で [`LiChaoTree`] と同じことを行う。ノードは必要になったときに生成する。
+/// 
+/// - `add_line`: `O(log W)`, `add_segment`: `O(log^2 W)`, `query`: `O(log W)` (`W = hi - lo`)
+pub struct DynamicLiChaoTree<T: LineNum, S: Select> {
+    lo: i64,
+    hi: i64,
+    /// `(line, [left, right])`, 子がないとき `!0`
+    pool: Vec<(Option<(T, T)>, [usize; 2])>,
+    history: Vec<(usize, Option<(T, T)>)>,
+    /// `snapshot` されてから `commit` されるまで `true`
+    record: bool,
+    _marker: PhantomData<S>,
+}
+
+impl<T: LineNum, S: Select> DynamicLiChaoTree<T, S> {
+    pub fn new(lo: i64, hi: i64) -> Self {
+        assert!(lo < hi);
+        Self { lo, hi, pool: vec![(None, [!0; 2])], history: vec![], record: false, _marker: PhantomData }
+    }
+    
+    /// 生成したノード数を返す。
+    pub fn pool_len(&self) -> usize { self.pool.len() }
+    
+    fn child(&mut self, k: usize, i: usize) -> usize {
+        if self.pool[k].1[i] == !0 {
+            self.pool.push((None, [!0; 2]));
+            self.pool[k].1[i] = self.pool.len()-1;
+        }
+        self.pool[k].1[i]
+    }
+    
+    fn assign(&mut self, k: usize, line: Option<(T, T)>) {
+        let old = std::mem::replace(&mut self.pool[k].0, line);
+        if self.record { self.history.push((k, old)); }
+    }
+    
+    fn insert(&mut self, mut k: usize, mut l: i64, mut r: i64, mut f: (T, T)) {
+        loop {
+            let Some(g) = self.pool[k].0 else { self.assign(k, Some(f)); return; };
+            let m = l + (r-l)/2;
+            let x = |i: i64| T::from_i64(i);
+            if S::better(&eval(f, x(m)), &eval(g, x(m))) {
+                self.assign(k, Some(f));
+                f = g;
+            }
+            if r-l == 1 { return; }
+            let g = self.pool[k].0.unwrap();
+            if S::better(&eval(f, x(l)), &eval(g, x(l))) {
+                (k, r) = (self.child(k, 0), m);
+            } else if S::better(&eval(f, x(r-1)), &eval(g, x(r-1))) {
+                (k, l) = (self.child(k, 1), m);
+            } else {
+                return;
+            }
+        }
+    }
+    
+    fn insert_segment(&mut self, k: usize, l: i64, r: i64, f: (T, T), sl: i64, sr: i64) {
+        if sr <= l || r <= sl { return; }
+        if sl <= l && r <= sr { self.insert(k, l, r, f); return; }
+        let m = l + (r-l)/2;
+        let c = self.child(k, 0);
+        self.insert_segment(c, l, m, f, sl, sr);
+        let c = self.child(k, 1);
+        self.insert_segment(c, m, r, f, sl, sr);
+    }
+    
+    /// 直線 `y = ax + b` を追加する。
+    pub fn add_line(&mut self, a: T, b: T) {
+        self.insert(0, self.lo, self.hi, (a, b));
+    }
+    
+    /// `x in range` の範囲のみに存在する線分 `y = ax + b` を追加する。
+    pub fn add_segment(&mut self, a: T, b: T, range: impl RangeBounds<i64>) {
+        let l = match range.start_bound() {
+            Bound::Included(&x) => x,
+            Bound::Excluded(&x) => x+1,
+            Bound::Unbounded => self.lo
+        }.max(self.lo);
+        let r = match range.end_bound() {
+            Bound::Included(&x) => x+1,
+            Bound::Excluded(&x) => x,
+            Bound::Unbounded => self.hi
+        }.min(self.hi);
+        if l < r { self.insert_segment(0, self.lo, self.hi, (a, b), l, r); }
+    }
+    
+    /// 点 `x` で最も良い直線 `(a, b)` を返す。直線が存在しないとき `None` を返す。
+    pub fn query_line(&self, x: i64) -> Option<(T, T)> {
+        assert!(self.lo <= x && x < self.hi);
+        let (mut k, mut l, mut r) = (0, self.lo, self.hi);
+        let tx = T::from_i64(x);
+        let mut res: Option<(T, T)> = None;
+        while k != !0 {
+            if let Some(f) = self.pool[k].0 {
+                if res.map_or(true, |g| S::better(&eval(f, tx), &eval(g, tx))) { res = Some(f); }
+            }
+            let m = l + (r-l)/2;
+            if x < m { k = self.pool[k].1[0]; r = m; } else { k = self.pool[k].1[1]; l = m; }
+        }
+        res
+    }
+    
+    /// 点 `x` における最小値・最大値を返す。直線が存在しないとき `None` を返す。
+    pub fn query(&self, x: i64) -> Option<T> { self.query_line(x).map(|f| eval(f, T::from_i64(x))) }
+    
+    /// 現在の状態を表す値を返す。[`DynamicLiChaoTree::rollback`] に渡すと、この状態に戻る。
+    /// 
+    /// これ以降 [`DynamicLiChaoTree::commit`] を呼ぶまで、追加のたびに変更履歴を記録する。
+    pub fn snapshot(&mut self) -> usize { self.record = true; self.history.len() }
+    
+    /// 変更履歴を破棄し、記録をやめる。それまでの `snapshot` の値は使えなくなる。
+    pub fn commit(&mut self) { self.history.clear(); self.record = false; }
+    
+    /// 生成したノードは残るが、直線は全て元に戻る。
+    pub fn rollback(&mut self, snapshot: usize) {
+        while self.history.len() > snapshot {
+            let (k, f) = self.history.pop().unwrap();
+            self.pool[k].0 = f;
+        }
+    }
+}
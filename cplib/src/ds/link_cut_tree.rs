@@ -0,0 +1,152 @@
+use std::ptr::NonNull;
+use crate::cplib::ds::splay_tree::{SplayOp, Node, NodeRef};
+
+
+/// Link-Cut Tree
+/// 
+/// 頂点 `0..n` からなる森を管理する。各頂点が [`SplayOp::Value`] を持ち、パス上の積・遅延作用ができる。
+/// 
+/// 内部の splay 木は [`SplayTree`](crate::cplib::ds::splay_tree::SplayTree) と共通である。パスは根側から順に並ぶので、
+/// 非可換な `Acc` でも `fold(u, v)` は `u` から `v` の順に積を取ったものになる。
+/// 
+/// # 搭載機能
+/// 
+/// - `link, cut, evert, root, connected, lca`
+/// - パス積・パス遅延作用 `fold, act`
+/// - 一点取得・更新 `get, set`
+pub struct LinkCutTree<Op: SplayOp> {
+    nodes: Vec<Node<Op>>,
+}
+
+impl<Op: SplayOp> LinkCutTree<Op> {
+    /// 各頂点の値が `values` で、辺を持たない森を作成する。
+    pub fn new(values: Vec<Op::Value>) -> Self {
+        Self { nodes: values.into_iter().map(Node::new).collect() }
+    }
+    
+    pub fn len(&self) -> usize { self.nodes.len() }
+    
+    fn node(&mut self, i: usize) -> NodeRef<Op> {
+        unsafe { NodeRef(NonNull::new_unchecked(self.nodes.as_mut_ptr().add(i))) }
+    }
+    
+    fn index(&self, p: NodeRef<Op>) -> usize {
+        unsafe { p.0.as_ptr().offset_from(self.nodes.as_ptr()) as usize }
+    }
+    
+    /// 根から `i` までのパスを 1 つの splay 木にし、`i` をその根にする。最後に splay した頂点を返す。
+    fn expose(&mut self, i: usize) -> NodeRef<Op> {
+        let x = self.node(i);
+        let (mut rp, mut cur) = (None, Some(x));
+        let mut last = x;
+        while let Some(mut c) = cur {
+            c.push_path();
+            c.splay();
+            c.child[1] = rp;
+            c.recalc();
+            rp = Some(c);
+            last = c;
+            cur = c.parent;
+        }
+        x.push_path();
+        x.splay();
+        last
+    }
+    
+    /// `i` を根にする。
+    pub fn evert(&mut self, i: usize) {
+        self.expose(i);
+        self.node(i).flip();
+    }
+    
+    /// `i` を含む木の根を返す。
+    pub fn root(&mut self, i: usize) -> usize {
+        self.expose(i);
+        let mut p = self.node(i);
+        loop {
+            p.push();
+            let Some(c) = p.child[0] else { break; };
+            p = c;
+        }
+        p.splay();
+        self.index(p)
+    }
+    
+    pub fn connected(&mut self, u: usize, v: usize) -> bool {
+        self.root(u) == self.root(v)
+    }
+    
+    /// 現在の根に関する `u, v` の LCA を返す。非連結のとき `None` を返す。
+    pub fn lca(&mut self, u: usize, v: usize) -> Option<usize> {
+        if !self.connected(u, v) { return None; }
+        self.expose(u);
+        let p = self.expose(v);
+        Some(self.index(p))
+    }
+    
+    /// 辺 `(u, v)` を追加する。
+    /// 
+    /// # Panics
+    /// 
+    /// if `u` and `v` are already connected.
+    pub fn link(&mut self, u: usize, v: usize) {
+        assert!(!self.connected(u, v), "already connected (@ LinkCutTree::link)");
+        self.evert(u);
+        self.expose(v);
+        let mut x = self.node(u);
+        x.parent = Some(self.node(v));
+    }
+    
+    /// 辺 `(u, v)` を削除する。
+    /// 
+    /// # Panics
+    /// 
+    /// if edge `(u, v)` does not exist.
+    pub fn cut(&mut self, u: usize, v: usize) {
+        self.evert(u);
+        self.expose(v);
+        let (mut x, mut y) = (self.node(u), self.node(v));
+        y.push();
+        assert!(y.child[0] == Some(x) && y.cnt[0] == 1, "edge not found (@ LinkCutTree::cut)");
+        y.child[0] = None;
+        x.parent = None;
+        y.recalc();
+    }
+    
+    /// パス `u - v` 上の頂点の値を `u` から順に畳み込んだ値を返す。
+    /// 
+    /// # Panics
+    /// 
+    /// if `u` and `v` are not connected.
+    pub fn fold(&mut self, u: usize, v: usize) -> Op::Acc {
+        assert!(self.connected(u, v), "not connected (@ LinkCutTree::fold)");
+        self.evert(u);
+        self.expose(v);
+        self.node(v).acc[0].clone()
+    }
+    
+    /// パス `u - v` 上の頂点に `lazy` を作用させる。
+    /// 
+    /// # Panics
+    /// 
+    /// if `u` and `v` are not connected.
+    pub fn act(&mut self, u: usize, v: usize, lazy: &Op::Lazy) {
+        assert!(self.connected(u, v), "not connected (@ LinkCutTree::act)");
+        self.evert(u);
+        self.expose(v);
+        self.node(v).apply(lazy);
+    }
+    
+    pub fn get(&mut self, i: usize) -> &Op::Value {
+        self.node(i).push_path();
+        &self.nodes[i].value
+    }
+    
+    pub fn set<T>(&mut self, i: usize, f: impl FnOnce(&mut Op::Value) -> T) -> T {
+        self.expose(i);
+        let mut x = self.node(i);
+        let res = f(&mut x.value);
+        x.recalc();
+        res
+    }
+}
@@ -0,0 +1,125 @@
+use std::ops::Add;
+
+
+/// Meldable Heap (leftist heap, 最小値)
+/// 
+/// ノードプール上で複数のヒープを管理する。ヒープは根の番号で表し、空のヒープは `!0` である。
+/// 
+/// - `meld, push, pop`: `O(log N)`
+/// - `add`: ヒープ全体に値を加える。`O(1)`
+/// - [`MeldableHeap::new`] で `persistent = true` とすると、更新系の関数はノードをコピーし、それまでの根もそのまま使える。
+/// 
+/// 最大値が欲しいときは符号を反転させて使う。
+pub struct MeldableHeap<T: Ord + Clone + Add<Output = T>> {
+    pool: Vec<Node<T>>,
+    persistent: bool,
+}
+
+/// `lazy` の対象は自身を含まない。
+#[derive(Clone)]
+struct Node<T> {
+    value: T,
+    lazy: Option<T>,
+    /// 右の背骨の長さ
+    rank: usize,
+    len: usize,
+    c: [usize; 2],
+}
+
+impl<T: Ord + Clone + Add<Output = T>> MeldableHeap<T> {
+    pub fn new(persistent: bool) -> Self { Self { pool: vec![], persistent } }
+    
+    /// 生成したノード数を返す。
+    pub fn pool_len(&self) -> usize { self.pool.len() }
+    
+    pub fn len(&self, root: usize) -> usize { if root == !0 { 0 } else { self.pool[root].len } }
+    fn rank(&self, root: usize) -> usize { if root == !0 { 0 } else { self.pool[root].rank } }
+    
+    /// 永続ならば `p` をコピーしたノードを、そうでなければ `p` を返す。
+    fn copy(&mut self, p: usize) -> usize {
+        if !self.persistent || p == !0 { return p; }
+        self.pool.push(self.pool[p].clone());
+        self.pool.len()-1
+    }
+    
+    /// `p` の部分木全体に `x` を加える。`p` は書き換えてよいノードである。
+    fn apply(&mut self, p: usize, x: &T) {
+        let node = &mut self.pool[p];
+        node.value = node.value.clone() + x.clone();
+        node.lazy = Some(match node.lazy.take() { Some(l) => l + x.clone(), None => x.clone() });
+    }
+    
+    /// `lazy[p]` を子に伝搬させる。`p` は書き換えてよいノードである。
+    fn push(&mut self, p: usize) {
+        let Some(lazy) = self.pool[p].lazy.take() else { return; };
+        for i in 0..2 {
+            let c = self.copy(self.pool[p].c[i]);
+            if c == !0 { continue; }
+            self.apply(c, &lazy);
+            self.pool[p].c[i] = c;
+        }
+    }
+    
+    /// 最小値を返す。
+    pub fn top(&self, root: usize) -> Option<&T> {
+        (root != !0).then(|| &self.pool[root].value)
+    }
+    
+    /// `a, b` を併合したヒープの根を返す。
+    pub fn meld(&mut self, a: usize, b: usize) -> usize {
+        if a == !0 { return b; }
+        if b == !0 { return a; }
+        let (a, b) = if self.pool[a].value <= self.pool[b].value { (a, b) } else { (b, a) };
+        let a = self.copy(a);
+        self.push(a);
+        let r = self.meld(self.pool[a].c[1], b);
+        let l = self.pool[a].c[0];
+        let c = if self.rank(l) < self.rank(r) { [r, l] } else { [l, r] };
+        let rank = self.rank(c[1])+1;
+        let len = self.len(c[0]) + self.len(c[1]) + 1;
+        let node = &mut self.pool[a];
+        node.c = c;
+        node.rank = rank;
+        node.len = len;
+        a
+    }
+    
+    /// `x` を追加したヒープの根を返す。
+    pub fn push_value(&mut self, root: usize, x: T) -> usize {
+        self.pool.push(Node { value: x, lazy: None, rank: 1, len: 1, c: [!0; 2] });
+        self.meld(root, self.pool.len()-1)
+    }
+    
+    /// 最小値を削除したヒープの根を返す。
+    /// 
+    /// # Panics
+    /// 
+    /// if `root` is empty.
+    pub fn pop(&mut self, root: usize) -> usize {
+        assert!(root != !0, "heap is empty (@ MeldableHeap::pop)");
+        let p = self.copy(root);
+        self.push(p);
+        let [l, r] = self.pool[p].c;
+        self.meld(l, r)
+    }
+    
+    /// 全ての要素に `x` を加えたヒープの根を返す。
+    pub fn add(&mut self, root: usize, x: T) -> usize {
+        if root == !0 { return !0; }
+        let p = self.copy(root);
+        self.apply(p, &x);
+        p
+    }
+    
+    /// ヒープの要素を昇順に返す。
+    pub fn to_vec(&mut self, mut root: usize) -> Vec<T> {
+        let persistent = std::mem::replace(&mut self.persistent, true);
+        let mut res = vec![];
+        while root != !0 {
+            res.push(self.pool[root].value.clone());
+            root = self.pop(root);
+        }
+        self.persistent = persistent;
+        res
+    }
+}
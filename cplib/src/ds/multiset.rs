@@ -2,6 +2,7 @@ use std::{collections::{BTreeMap, HashMap}, hash::Hash, ops::RangeBounds, ptr::e
 
 pub use {
     btree_multi_set::BTreeMultiSet,
+    ordered_multi_set::OrderedMultiSet,
     hash_multi_set::HashMultiSet
 };
 
@@ -68,7 +69,7 @@ mod btree_multi_set {
         
         
         // implement only for BTree
-        pub fn iter(&self) -> Iter<V> { Iter::new(self) }
+        pub fn iter(&self) -> Iter<'_, V> { Iter::new(self) }
         pub fn iter_blocks(&self) -> impl Iterator<Item = (&V, usize)> + DoubleEndedIterator { self.inner.iter().map(|(v, &n)| (v, n)) }
         pub fn new() -> Self { Self { inner: BTreeMap::new(), len: 0 } }
         pub fn first(&self) -> Option<(&V, usize)> { self.inner.first_key_value().map(|v| (v.0, *v.1)) }
@@ -142,7 +143,7 @@ mod btree_multi_set {
             *bidx -= 1;
             let res = BlockItem { value: b.0, len: *b.1, idx: *bidx };
             if ptr_eq(f.0, b.0) && fidx == bidx { *self = Self::Empty; return Some(res); }
-            if *bidx == 0 { *b = src.next().unwrap_or(*f); *bidx = *b.1; }
+            if *bidx == 0 { *b = src.next_back().unwrap_or(*f); *bidx = *b.1; }
             Some(res)
         }
     }
@@ -150,6 +151,148 @@ mod btree_multi_set {
 
 
 
+mod ordered_multi_set {
+    use super::*;
+    use std::{cmp::Ordering, fmt::Debug, marker::PhantomData};
+    use crate::cplib::ds::ordered::{Ordered, OrderedOp};
+    
+    /// 順序統計を求められる Multi な `BTreeSet`
+    /// 
+    /// 値ごとの個数を [`Ordered`] で管理する。ブロック数を `B` として、各操作は `O(log B)` (amortized) である。
+    /// [`BTreeMultiSet`] より定数倍が重いので、順序統計 `nth, rank, count_range, median, quantile` が必要なときに使う。
+    /// 
+    /// implemented: `FromIterator<V>`
+    #[derive(Clone)]
+    pub struct OrderedMultiSet<V: Ord + Clone> {
+        inner: Ordered<Counter<V>>,
+        len: usize
+    }
+    
+    struct Counter<V>(PhantomData<V>);
+    
+    impl<V: Ord> OrderedOp for Counter<V> {
+        type Key = V;
+        type Value = usize;
+        type Acc = usize;
+        fn cmp_key(l: &V, r: &V) -> Ordering { l.cmp(r) }
+        fn to_acc(v: &usize) -> usize { *v }
+        fn prod_acc(lhs: &usize, rhs: &usize) -> usize { lhs + rhs }
+    }
+    
+    impl<V: Ord + Clone> OrderedMultiSet<V> {
+        pub fn new() -> Self { Self { inner: Ordered::new(), len: 0 } }
+        pub fn clear(&mut self) { self.inner = Ordered::new(); self.len = 0; }
+        pub fn contains(&self, value: &V) -> bool { self.inner.contains_key(value) }
+        /// `value` を `n` 個追加する。新規追加するとき `V::clone` が発生する。
+        pub fn insert(&mut self, value: &V, n: usize) { self.modify(value, |befn| befn+n); }
+        pub fn is_empty(&self) -> bool { self.len == 0 }
+        /// ブロック値の和を返す。`{a: 3, b: 4} => 7`
+        pub fn len(&self) -> usize { self.len }
+        /// `value` を `n` 個削除する。成功したら `true` を返す。
+        /// 
+        /// - `strict = true` のとき、`0..n` 個ならば削除せず、失敗とする。
+        /// - `strict = false` のとき、`1..n` 個ならば全て削除し、成功とする。`0` 個のときは、失敗とする。
+        pub fn remove(&mut self, value: &V, n: usize, strict: bool) -> bool {
+            let mut ret = true;
+            self.modify(value, |befn| {
+                let fail = if strict { befn < n } else { befn == 0 };
+                if fail { ret = false; befn } else { befn.saturating_sub(n) }
+            });
+            ret
+        }
+        pub fn remove_block(&mut self, value: &V) -> Option<usize> {
+            let mut ret = None;
+            self.modify(value, |n| { if n != 0 { ret = Some(n); } 0 });
+            ret
+        }
+        
+        /// ブロック数を返す。`{a: 3, b: 4} => 2`
+        pub fn len_blocks(&self) -> usize { self.inner.len() }
+        /// ブロック `value` の値を返す。
+        pub fn len_block(&self, value: &V) -> usize { *self.inner.get(value).unwrap_or(&0) }
+        /// ブロックの値を取得・変更できる。新規追加するとき `value.clone()` が発生する。
+        pub fn modify(&mut self, value: &V, f: impl FnOnce(usize) -> usize) {
+            let bef = self.len_block(value);
+            let n = f(bef);
+            self.len = self.len - bef + n;
+            if bef == 0 {
+                if n != 0 { self.inner.insert(value.clone(), n); }
+            } else if n == 0 {
+                self.inner.remove(value);
+            } else {
+                self.inner.modify(value, |c| *c = n);
+            }
+        }
+        
+        pub fn iter_blocks(&self) -> impl DoubleEndedIterator<Item = (&V, usize)> { self.inner.iter().map(|(v, &n)| (v, n)) }
+        pub fn range_blocks(&self, range: impl RangeBounds<V>) -> impl DoubleEndedIterator<Item = (&V, usize)> { self.inner.range(range).map(|(v, &n)| (v, n)) }
+        pub fn first(&self) -> Option<(&V, usize)> { self.inner.nth(0).map(|v| (v.0, *v.1)) }
+        pub fn last(&self) -> Option<(&V, usize)> { self.inner.nth(self.len_blocks().wrapping_sub(1)).map(|v| (v.0, *v.1)) }
+        /// 先頭のブロックから一つ削除して取り出す。ただし、`V::clone()` が発生する。
+        pub fn pop_first(&mut self) -> Option<V> {
+            let v = self.first()?.0.clone();
+            self.modify(&v, |n| n-1);
+            Some(v)
+        }
+        /// 末尾のブロックから一つ削除して取り出す。ただし、`V::clone()` が発生する。
+        pub fn pop_last(&mut self) -> Option<V> {
+            let v = self.last()?.0.clone();
+            self.modify(&v, |n| n-1);
+            Some(v)
+        }
+        
+        /// 小さい方から `k` 番目 (0-indexed) の値を返す。
+        pub fn nth(&self, k: usize) -> Option<&V> {
+            if k >= self.len { return None; }
+            let i = self.inner.max_right(|&s| s <= k);
+            self.inner.nth(i).map(|e| e.0)
+        }
+        /// `value` 未満の値の個数を返す。
+        pub fn rank(&self, value: &V) -> usize {
+            let i = self.inner.lower_bound(value);
+            self.inner.fold(..i).unwrap_or(0)
+        }
+        /// `range` に含まれる値の個数を返す。
+        pub fn count_range(&self, range: impl RangeBounds<V>) -> usize {
+            self.inner.fold_key(range).unwrap_or(0)
+        }
+        /// 中央値を返す。`len` が偶数のときは小さい方を返す。
+        pub fn median(&self) -> Option<&V> { self.quantile(1, 2) }
+        /// 小さい方から `floor((len-1) * num / den)` 番目の値を返す。
+        pub fn quantile(&self, num: usize, den: usize) -> Option<&V> {
+            assert!(num <= den && den != 0);
+            if self.len == 0 { return None; }
+            self.nth((self.len-1) * num / den)
+        }
+    }
+    
+    impl<V: Ord + Clone> Default for OrderedMultiSet<V> {
+        fn default() -> Self { Self::new() }
+    }
+    
+    impl<V: Ord + Clone> PartialEq for OrderedMultiSet<V> {
+        fn eq(&self, other: &Self) -> bool { self.len == other.len && self.iter_blocks().eq(other.iter_blocks()) }
+    }
+    
+    impl<V: Ord + Clone> Eq for OrderedMultiSet<V> {}
+    
+    impl<V: Ord + Clone + Debug> Debug for OrderedMultiSet<V> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("OrderedMultiSet").field("inner", &self.inner).field("len", &self.len).finish()
+        }
+    }
+    
+    impl<V: Ord + Clone> FromIterator<V> for OrderedMultiSet<V> {
+        fn from_iter<T: IntoIterator<Item = V>>(iter: T) -> Self {
+            let mut res = Self::new();
+            for v in iter { res.insert(&v, 1); }
+            res
+        }
+    }
+}
+
+
+
 mod hash_multi_set {
     use super::*;
     use std::collections::hash_map::Iter as HIter;
@@ -203,7 +346,7 @@ mod hash_multi_set {
         
         
         // implement only for Hash
-        pub fn iter(&self) -> Iter<V> { Iter::new(self) }
+        pub fn iter(&self) -> Iter<'_, V> { Iter::new(self) }
         pub fn iter_blocks(&self) -> impl Iterator<Item = (&V, usize)> { self.inner.iter().map(|(v, &n)| (v, n)) }
         pub fn new() -> Self { Self { inner: HashMap::new(), len: 0} }
     }
@@ -0,0 +1,573 @@
+use std::{cell::Cell, cmp::Ordering, fmt::Debug, marker::PhantomData, ops::{Bound, Deref, DerefMut, RangeBounds}, ptr::NonNull};
+use crate::cplib::util::func::to_bounds;
+
+
+/// [`Ordered`] に載せる演算用 trait
+pub trait OrderedOp {
+    type Key;
+    type Value;
+    type Acc: Clone;
+    
+    fn cmp_key(l: &Self::Key, r: &Self::Key) -> Ordering;
+    fn to_acc(v: &Self::Value) -> Self::Acc;
+    fn prod_acc(lhs: &Self::Acc, rhs: &Self::Acc) -> Self::Acc;
+}
+
+/// [`OrderedOp::Key`] によって順序付けられた列。
+/// 
+/// 内部は splay 木である。同じ key を複数持つことができ、その間の順序は挿入順の逆になる。
+/// 
+/// # 搭載機能
+/// 
+/// - key による挿入・削除・取得 `insert, insert_replace, remove, get, modify`
+/// - 順序統計 `nth, rank, lower_bound, upper_bound, partition_point, max_right`
+/// - 区間積 `fold, fold_key`
+/// - 分割・連結 `split_at, split3_key, merge, append`
+/// - `iter, range`
+/// - `Clone`, `Debug`, `FromIterator<(Op::Key, Op::Value)>`
+/// 
+/// # Memo
+/// 
+/// splay しかしない関数は `&self` で、そうでない破壊的関数は `&mut self` にしてある。
+pub struct Ordered<Op: OrderedOp>(Cell<Option<NodeRef<Op>>>);
+
+impl<Op: OrderedOp> Ordered<Op> {
+    pub fn new() -> Self { Self(None.into()) }
+    pub fn is_empty(&self) -> bool { self.0.get().is_none() }
+    pub fn len(&self) -> usize { self.0.get().map_or(0, |r| r.len()) }
+    
+    fn splay(&self, ptr: NodeRef<Op>) {
+        ptr.splay();
+        self.0.set(Some(ptr));
+    }
+    
+    /// # Panics
+    /// 
+    /// if `Ordered` is empty.
+    pub fn root(&self) -> &Op::Value {
+        unsafe { &self.0.get().unwrap().0.as_ref().value }
+    }
+    
+    /// # Panics
+    /// 
+    /// if `Ordered` is empty.
+    pub fn root_mut<T>(&mut self, f: impl FnOnce(&mut Op::Value) -> T) -> T {
+        let mut r = self.0.get().unwrap();
+        let res = f(&mut r.value);
+        r.update();
+        res
+    }
+    
+    /// `idx` 番目のノードを root にして、その key と value を返す。範囲外のとき `None` を返す。
+    pub fn nth(&self, idx: usize) -> Option<(&Op::Key, &Op::Value)> {
+        if !(idx < self.len()) { return None; }
+        let p = self.0.get().unwrap().nth(idx);
+        self.0.set(Some(p));
+        Some(p.entry())
+    }
+    
+    /// `pred(idx-1) == true && pred(idx) = false` となる `idx` を返す。
+    ///
+    /// `idx < self.len()` ならば `idx` 番目のノードを、そうでなければ一番右のノードを根にする。
+    pub fn partition_point(&self, pred: impl Fn(&Op::Key) -> bool) -> usize {
+        let Some(mut ptr) = self.0.get() else { return 0; };
+        let (mut res, mut tmp) = (0, None);
+        loop {
+            let pos = pred(&ptr.key);
+            if pos { res += ptr.cnt[0]+1; } else { tmp = Some(ptr); }
+            let Some(c) = ptr.child[pos as usize] else { break; };
+            ptr = c;
+        }
+        self.splay(tmp.unwrap_or(ptr));
+        res
+    }
+    
+    /// `f(fold(..r)) == true` となる最大の `r` を返す。ただし `f` は単調であり、`fold(..0)` については `true` とみなす。
+    pub fn max_right(&self, f: impl Fn(&Op::Acc) -> bool) -> usize {
+        let Some(mut ptr) = self.0.get() else { return 0; };
+        let (mut res, mut acc): (usize, Option<Op::Acc>) = (0, None);
+        let prod = |acc: &Option<Op::Acc>, x: &Op::Acc| acc.as_ref().map_or(x.clone(), |a| Op::prod_acc(a, x));
+        loop {
+            if let Some(c) = ptr.child[0] {
+                let tmp = prod(&acc, &c.acc);
+                if !f(&tmp) { ptr = c; continue; }
+                acc = Some(tmp);
+            }
+            let tmp = prod(&acc, &Op::to_acc(&ptr.value));
+            if !f(&tmp) { res += ptr.cnt[0]; break; }
+            acc = Some(tmp);
+            res += ptr.cnt[0]+1;
+            let Some(c) = ptr.child[1] else { break; };
+            ptr = c;
+        }
+        self.splay(ptr);
+        res
+    }
+    
+    /// `[self[..idx], self[idx..]]` に分割する。はみ出してもよい。
+    pub fn split_at(self, idx: usize) -> [Self; 2] {
+        let idx = idx.min(self.len());
+        let [l, r] = split_at(self.0.take(), idx);
+        [Ordered(l.into()), Ordered(r.into())]
+    }
+    
+    /// key が `range` の外側で左側・内側・外側で右側の 3 つに分割する。
+    pub fn split3_key(self, range: impl RangeBounds<Op::Key>) -> [Self; 3] {
+        let [l, r] = self.key_bounds(range);
+        let [mid, r] = self.split_at(r);
+        let [l, mid] = mid.split_at(l);
+        [l, mid, r]
+    }
+    
+    /// `l` と `r` を連結する。`l` の key は全て `r` の key 以下でなければならない。
+    pub fn merge(l: Self, r: Self) -> Self {
+        Ordered(merge(l.0.take(), r.0.take()).into())
+    }
+    
+    /// `other` の要素を全て `self` に挿入する。key の大小関係は任意である。
+    /// 
+    /// 小さい方を大きい方へ昇順に挿入するので、`O(m log(n/m + 1))` (amortized) となる。
+    pub fn append(&mut self, other: &mut Self) {
+        if self.len() < other.len() { std::mem::swap(self, other); }
+        let mut nodes = vec![];
+        let mut stk = vec![];
+        let mut cur = other.0.take();
+        while cur.is_some() || !stk.is_empty() {
+            while let Some(p) = cur { stk.push(p); cur = p.child[0]; }
+            let mut p = stk.pop().unwrap();
+            cur = p.child[1];
+            p.child = [None, None]; p.parent = None;
+            nodes.push(p);
+        }
+        for p in nodes { p.update(); self.insert_node(p); }
+    }
+    
+    
+    
+    /// lower bound を求める。
+    /// 
+    /// `A[root-1] < key <= A[root]` となる `root` が存在すれば根にする。存在しない場合は、一番右のノードを根にする。
+    pub fn lower_bound(&self, key: &Op::Key) -> usize {
+        self.partition_point(|k| Op::cmp_key(k, key).is_lt())
+    }
+    
+    /// upper bound を求める。
+    /// 
+    /// `A[root-1] <= key < A[root]` となる `root` が存在すれば根にする。存在しない場合は、一番右のノードを根にする。
+    pub fn upper_bound(&self, key: &Op::Key) -> usize {
+        self.partition_point(|k| Op::cmp_key(k, key).is_le())
+    }
+    
+    /// `key` 未満の要素数を返す。`self.lower_bound(key)` と同じ。
+    pub fn rank(&self, key: &Op::Key) -> usize { self.lower_bound(key) }
+    
+    /// key が `key` と等しいノードのうち一番左を根にして、返す。
+    fn find(&self, key: &Op::Key) -> Option<NodeRef<Op>> {
+        let idx = self.lower_bound(key);
+        if idx == self.len() { return None; }
+        let p = self.0.get().unwrap();
+        Op::cmp_key(&p.key, key).is_eq().then_some(p)
+    }
+    
+    pub fn contains_key(&self, key: &Op::Key) -> bool { self.find(key).is_some() }
+    
+    /// key が `key` と等しい要素のうち一番左の value を返す。
+    pub fn get(&self, key: &Op::Key) -> Option<&Op::Value> {
+        self.find(key).map(|p| unsafe { &(*p.0.as_ptr()).value })
+    }
+    
+    /// key が `key` と等しい要素のうち一番左の value に `f` を適用する。
+    pub fn modify<T>(&mut self, key: &Op::Key, f: impl FnOnce(&mut Op::Value) -> T) -> Option<T> {
+        let mut p = self.find(key)?;
+        let res = f(&mut p.value);
+        p.update();
+        Some(res)
+    }
+    
+    /// key が `key` と等しい要素のうち一番左を削除して返す。
+    pub fn remove(&mut self, key: &Op::Key) -> Option<(Op::Key, Op::Value)> {
+        let idx = self.lower_bound(key);
+        if idx == self.len() || !Op::cmp_key(&self.0.get().unwrap().key, key).is_eq() { return None; }
+        Some(self.remove_nth(idx))
+    }
+    
+    /// `idx` 番目の要素を削除して返す。
+    /// 
+    /// # Panics
+    /// 
+    /// if not `idx < self.len()`
+    pub fn remove_nth(&mut self, idx: usize) -> (Op::Key, Op::Value) {
+        assert!(idx < self.len());
+        let [l, r] = split_at(self.0.get(), idx);
+        let [m, r] = split_at(r, 1);
+        self.0.set(merge(l, r));
+        let node = unsafe { Box::from_raw(m.unwrap().0.as_ptr()) };
+        (node.key, node.value)
+    }
+    
+    /// `node` を key が等しい区間の一番左に挿入する。
+    fn insert_node(&mut self, node: NodeRef<Op>) {
+        let idx = self.lower_bound(&node.key);
+        let Some(mut p) = self.0.get() else { self.0.set(Some(node)); return; };
+        if idx == self.len() { node.set_child(Some(p), false); node.update(); self.0.set(Some(node)); return; }
+        node.set_child(p.child[0], false);
+        node.set_child(Some(p), true);
+        p.child[0] = None;
+        p.update(); node.update();
+        self.0.set(Some(node));
+    }
+    
+    /// `key` が等しい区間の一番左に挿入する。
+    pub fn insert(&mut self, key: Op::Key, value: Op::Value) {
+        self.insert_node(NodeRef::new(key, value));
+    }
+    
+    /// `key` が存在すれば `update` を適用し、存在しなければ `init` を挿入する。
+    pub fn insert_replace(&mut self, key: Op::Key, init: Op::Value, update: impl FnOnce(&mut Op::Value)) {
+        if let Some(mut p) = self.find(&key) { update(&mut p.value); p.update(); return; }
+        self.insert_node(NodeRef::new(key, init));
+    }
+    
+    /// key の区間 `range` に対応するインデックスの区間を返す。
+    fn key_bounds(&self, range: impl RangeBounds<Op::Key>) -> [usize; 2] {
+        let r = match range.end_bound() {
+            Bound::Included(x) => self.upper_bound(x),
+            Bound::Excluded(x) => self.lower_bound(x),
+            Bound::Unbounded => self.len()
+        };
+        let l = match range.start_bound() {
+            Bound::Included(x) => self.lower_bound(x),
+            Bound::Excluded(x) => self.upper_bound(x),
+            Bound::Unbounded => 0
+        };
+        [l.min(r), r]
+    }
+    
+    /// `self[range]` の積を返す。区間が空のとき `None` を返す。
+    pub fn fold(&self, range: impl RangeBounds<usize>) -> Option<Op::Acc> {
+        let [l, r] = to_bounds(range, self.len());
+        if l == r { return None; }
+        let [mid, rr] = split_at(self.0.get(), r);
+        let [ll, mid] = split_at(mid, l);
+        let res = mid.unwrap().acc.clone();
+        self.0.set(merge(merge(ll, mid), rr));
+        Some(res)
+    }
+    
+    /// key が `range` に含まれる要素の積を返す。空のとき `None` を返す。
+    pub fn fold_key(&self, range: impl RangeBounds<Op::Key>) -> Option<Op::Acc> {
+        let [l, r] = self.key_bounds(range);
+        self.fold(l..r)
+    }
+    
+    /// `self.into_iter() == self.iter()`
+    pub fn iter(&self) -> Iter<'_, Op> { self.iter_at(0, self.len()) }
+    
+    /// key が `range` に含まれる要素を順に返す [`Iterator`] を返す。
+    pub fn range(&self, range: impl RangeBounds<Op::Key>) -> Iter<'_, Op> {
+        let [l, r] = self.key_bounds(range);
+        self.iter_at(l, r)
+    }
+    
+    fn iter_at(&self, l: usize, r: usize) -> Iter<'_, Op> {
+        let (mut front, mut back) = (vec![], vec![]);
+        if l != r {
+            let root = self.0.get().unwrap();
+            let (mut p, mut i) = (root, l);
+            loop {
+                if i < p.cnt[0] { front.push(p); p = p.child[0].unwrap(); }
+                else if i == p.cnt[0] { front.push(p); break; }
+                else { i -= p.cnt[0]+1; p = p.child[1].unwrap(); }
+            }
+            let (mut p, mut i) = (root, r-1);
+            loop {
+                if i > p.cnt[0] { back.push(p); i -= p.cnt[0]+1; p = p.child[1].unwrap(); }
+                else if i == p.cnt[0] { back.push(p); break; }
+                else { p = p.child[0].unwrap(); }
+            }
+        }
+        Iter { front, back, rem: r-l, _marker: PhantomData }
+    }
+    
+    
+    
+    pub fn next(&mut self) -> bool {
+        let Some(p) = self.0.get() else { return false; };
+        let Some(ptr) = p.child[1] else { return false; };
+        self.splay(ptr.first());
+        true
+    }
+    
+    pub fn back(&mut self) -> bool {
+        let Some(p) = self.0.get() else { return false; };
+        let Some(ptr) = p.child[0] else { return false; };
+        self.splay(ptr.last());
+        true
+    }
+    
+    pub fn first(&self) {
+        let Some(mut ptr) = self.0.get() else { return; };
+        ptr = ptr.first();
+        self.splay(ptr);
+    }
+
+    pub fn last(&self) {
+        let Some(mut ptr) = self.0.get() else { return; };
+        ptr = ptr.last();
+        self.splay(ptr);
+    }
+}
+
+impl<Op: OrderedOp> FromIterator<(Op::Key, Op::Value)> for Ordered<Op> {
+    fn from_iter<T: IntoIterator<Item = (Op::Key, Op::Value)>>(iter: T) -> Self {
+        let mut res = Self::new();
+        for (k, v) in iter { res.insert(k, v); }
+        res
+    }
+}
+
+impl<'a, Op: OrderedOp> IntoIterator for &'a Ordered<Op> {
+    type Item = (&'a Op::Key, &'a Op::Value);
+    type IntoIter = Iter<'a, Op>;
+    fn into_iter(self) -> Self::IntoIter { self.iter() }
+}
+
+impl<Op: OrderedOp> Clone for Ordered<Op> where Op::Key: Clone, Op::Value: Clone {
+    fn clone(&self) -> Self {
+        let mut root = None;
+        for (k, v) in self.iter() {
+            let node = NodeRef::new(k.clone(), v.clone());
+            node.set_child(root, false);
+            node.update();
+            root = Some(node);
+        }
+        Self(root.into())
+    }
+}
+
+impl<Op: OrderedOp> Debug for Ordered<Op> where Op::Key: Debug, Op::Value: Debug {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", debug(self.0.get(), f.alternate()))
+    }
+}
+
+impl<Op: OrderedOp> Drop for Ordered<Op> {
+    fn drop(&mut self) {
+        let mut stk: Vec<_> = self.0.take().into_iter().collect();
+        while let Some(p) = stk.pop() {
+            stk.extend(p.child.into_iter().flatten());
+            unsafe { drop(Box::from_raw(p.0.as_ptr())); }
+        }
+    }
+}
+
+
+
+/// [`Ordered::iter`], [`Ordered::range`] の返り値型。
+/// 
+/// # Constraints
+/// 
+/// [`Iter`] 存在中に [`Ordered`] を操作しない。
+pub struct Iter<'a, Op: OrderedOp> {
+    front: Vec<NodeRef<Op>>,
+    back: Vec<NodeRef<Op>>,
+    rem: usize,
+    _marker: PhantomData<&'a Ordered<Op>>,
+}
+
+impl<'a, Op: OrderedOp> Iterator for Iter<'a, Op> {
+    type Item = (&'a Op::Key, &'a Op::Value);
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rem == 0 { return None; }
+        self.rem -= 1;
+        let p = self.front.pop().unwrap();
+        let mut c = p.child[1];
+        while let Some(q) = c { self.front.push(q); c = q.child[0]; }
+        Some(p.entry())
+    }
+    
+    fn size_hint(&self) -> (usize, Option<usize>) { (self.rem, Some(self.rem)) }
+}
+
+impl<'a, Op: OrderedOp> DoubleEndedIterator for Iter<'a, Op> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.rem == 0 { return None; }
+        self.rem -= 1;
+        let p = self.back.pop().unwrap();
+        let mut c = p.child[0];
+        while let Some(q) = c { self.back.push(q); c = q.child[1]; }
+        Some(p.entry())
+    }
+}
+
+impl<'a, Op: OrderedOp> ExactSizeIterator for Iter<'a, Op> {}
+
+
+
+/// `root[..at]`, `root[at..]` を返す。
+fn split_at<Op: OrderedOp>(root: Option<NodeRef<Op>>, at: usize) -> [Option<NodeRef<Op>>; 2] {
+    let Some(root) = root else { return [None, None]; };
+    if at == 0 { return [None, Some(root)]; }
+    if at == root.len() { return [Some(root), None]; }
+    let mut r = root.nth(at);
+    let l = r.child[0].take();
+    if let Some(mut l) = l { l.parent = None; }
+    r.update();
+    [l, Some(r)]
+}
+
+/// `l`, `r` を連結した木の根を返す。
+fn merge<Op: OrderedOp>(l: Option<NodeRef<Op>>, r: Option<NodeRef<Op>>) -> Option<NodeRef<Op>> {
+    let Some(l) = l else { return r; };
+    let Some(_) = r else { return Some(l); };
+    let l = l.last();
+    l.splay();
+    l.set_child(r, true);
+    l.update();
+    Some(l)
+}
+
+
+
+struct Node<Op: OrderedOp> {
+    parent: Option<NodeRef<Op>>,
+    child: [Option<NodeRef<Op>>; 2],
+    key: Op::Key,
+    value: Op::Value,
+    acc: Op::Acc,
+    /// [左子のサイズ, 右子のサイズ]
+    cnt: [usize; 2]
+}
+
+struct NodeRef<Op: OrderedOp>(NonNull<Node<Op>>);
+
+impl<Op: OrderedOp> NodeRef<Op> {
+    fn new(key: Op::Key, value: Op::Value) -> Self {
+        unsafe {
+            NodeRef(NonNull::new_unchecked(Box::leak(Box::new(Node { parent: None, child: [None, None], key, acc: Op::to_acc(&value), value, cnt: [0, 0] }))))
+        }
+    }
+    
+    fn len(self) -> usize { self.cnt[0] + self.cnt[1] + 1 }
+    
+    fn entry<'a>(self) -> (&'a Op::Key, &'a Op::Value) {
+        unsafe { let node = &*self.0.as_ptr(); (&node.key, &node.value) }
+    }
+    
+    fn set_child(mut self, c: Option<Self>, pos: bool) {
+        self.child[pos as usize] = c;
+        if let Some(mut c) = c { c.parent = Some(self); }
+    }
+    
+    /// `p` の位置に `self` が来るよう rotate する。
+    /// 
+    /// # Panics
+    /// 
+    /// if `self.parent == None`
+    fn rotate(mut self, p: Self, pos: bool) {
+        self.parent = p.parent;
+        if let Some(pp) = p.parent { pp.set_child(Some(self), pp.child[1] == Some(p)); }
+        p.set_child(self.child[!pos as usize], pos);
+        self.set_child(Some(p), !pos);
+        p.update(); self.update();
+    }
+    
+    /// `self` が根になるように適切に回転する。
+    fn splay(self) {
+        while let Some(p) = self.parent {
+            let pos = p.child[1] == Some(self);
+            let Some(pp) = p.parent else { self.rotate(p, pos); return; };
+            if pos == (pp.child[1] == Some(p)) {
+                // zig-zig
+                p.rotate(pp, pos); self.rotate(p, pos);
+            } else {
+                // zig-zag
+                self.rotate(p, pos); self.rotate(pp, !pos);
+            }
+        }
+    }
+    
+    /// 根 `self` の部分木で `idx` 番目のノードを splay して返す。
+    fn nth(self, mut idx: usize) -> Self {
+        let mut ptr = self;
+        loop {
+            match idx.cmp(&ptr.cnt[0]) {
+                Ordering::Less => { ptr = ptr.child[0].unwrap(); }
+                Ordering::Equal => { ptr.splay(); return ptr; }
+                Ordering::Greater => { idx -= ptr.cnt[0]+1; ptr = ptr.child[1].unwrap(); }
+            }
+        }
+    }
+    
+    /// `cnt`, `acc` を再計算する。
+    fn update(mut self) {
+        self.cnt = [0, 0];
+        self.acc = Op::to_acc(&self.value);
+        if let Some(c) = self.child[0] {
+            self.cnt[0] = c.len();
+            self.acc = Op::prod_acc(&c.acc, &self.acc);
+        }
+        if let Some(c) = self.child[1] {
+            self.cnt[1] = c.len();
+            self.acc = Op::prod_acc(&self.acc, &c.acc);
+        }
+    }
+    
+    
+    fn first(mut self) -> NodeRef<Op> {
+        while let Some(c) = self.child[0] { self = c; }
+        self
+    }
+    
+    fn last(mut self) -> NodeRef<Op> {
+        while let Some(c) = self.child[1] { self = c; }
+        self
+    }
+}
+
+
+impl<Op: OrderedOp> Deref for NodeRef<Op> {
+    type Target = Node<Op>; fn deref(&self) -> &Self::Target { unsafe { self.0.as_ref() } }
+}
+
+impl<Op: OrderedOp> DerefMut for NodeRef<Op> {
+    fn deref_mut(&mut self) -> &mut Self::Target { unsafe { self.0.as_mut() } }
+}
+
+impl<Op: OrderedOp> Clone for NodeRef<Op> { fn clone(&self) -> Self { Self(self.0) } }
+
+impl<Op: OrderedOp> Copy for NodeRef<Op> {}
+
+impl<Op: OrderedOp> PartialEq for NodeRef<Op> {
+    fn eq(&self, other: &Self) -> bool { self.0 == other.0 }
+}
+impl<Op: OrderedOp> Eq for NodeRef<Op> {}
+
+
+
+fn debug<Op: OrderedOp>(ptr: Option<NodeRef<Op>>, alt: bool) -> String where Op::Key: Debug, Op::Value: Debug {
+    let mut li = String::new();
+    let Some(p) = ptr else { return String::from("{}"); };
+    let mut stk = vec![(p, true, 0)];
+    while let Some((p, is_in, indent)) = stk.pop() {
+        if is_in {
+            if let Some(r) = p.child[1] { stk.push((r, true, indent+1)); }
+            stk.push((p, false, indent));
+            if let Some(l) = p.child[0] { stk.push((l, true, indent+1)); }
+        } else {
+            if alt {
+                li += "\n";
+                for _ in 0..indent { li += "    "; }
+                li += &format!("key={:?}, value={:?}", p.key, p.value);
+            } else {
+                li += &format!("{:?}: {:?}, ", p.key, p.value);
+            }
+        }
+    }
+    if alt {
+        li.remove(0); li
+    } else {
+        format!("{{{}}}", &li[..li.len()-2])
+    }
+}
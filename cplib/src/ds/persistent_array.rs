@@ -0,0 +1,77 @@
+//! 永続配列
+
+/// 永続配列
+/// 
+/// 完全 `B` 分木の path copying で実装している。根の index を版として扱い、初期状態の版は `0` である。
+/// `B` は 2 べきでなければならない。
+/// 
+/// - `get`: `O(log_B N)`
+/// - `set`: `O(B log_B N)`, `O(log_B N)` 個のノードを生成する。
+pub struct PersistentArray<T: Clone, const B: usize = 2> {
+    len: usize,
+    depth: usize,
+    pool: Vec<Node<T, B>>,
+}
+
+#[derive(Clone)]
+enum Node<T, const B: usize> {
+    Inner([usize; B]),
+    Leaf(T),
+}
+
+impl<T: Clone, const B: usize> PersistentArray<T, B> {
+    const LOG: usize = B.trailing_zeros() as usize;
+    
+    pub fn new(a: Vec<T>) -> Self {
+        assert!(B >= 2 && B.is_power_of_two());
+        let mut depth = 0;
+        while 1 << (depth * Self::LOG) < a.len() { depth += 1; }
+        let mut res = Self { len: a.len(), depth, pool: vec![] };
+        let mut a = a.into_iter();
+        if res.len != 0 { res.build(&mut a, depth); }
+        res
+    }
+    
+    /// 前順で確保するため、根は `0` になる。
+    fn build(&mut self, a: &mut impl Iterator<Item = T>, d: usize) -> usize {
+        let i = self.pool.len();
+        if d == 0 { self.pool.push(Node::Leaf(a.next().unwrap())); return i; }
+        self.pool.push(Node::Inner([!0; B]));
+        let mut c = [!0; B];
+        for c in &mut c {
+            if a.size_hint().1 == Some(0) { break; }
+            *c = self.build(a, d-1);
+        }
+        self.pool[i] = Node::Inner(c);
+        i
+    }
+    
+    pub fn len(&self) -> usize { self.len }
+    
+    pub fn get(&self, root: usize, i: usize) -> &T {
+        assert!(i < self.len);
+        let mut p = root;
+        for d in (0..self.depth).rev() {
+            let Node::Inner(c) = &self.pool[p] else { unreachable!() };
+            p = c[i >> (d * Self::LOG) & B-1];
+        }
+        let Node::Leaf(v) = &self.pool[p] else { unreachable!() };
+        v
+    }
+    
+    /// `root` の `i` 番目を `v` にした版を返す。
+    pub fn set(&mut self, root: usize, i: usize, v: T) -> usize {
+        assert!(i < self.len);
+        let res = self.pool.len();
+        let mut p = root;
+        for d in (0..self.depth).rev() {
+            let Node::Inner(mut c) = self.pool[p] else { unreachable!() };
+            let k = i >> (d * Self::LOG) & B-1;
+            p = c[k];
+            c[k] = self.pool.len()+1;
+            self.pool.push(Node::Inner(c));
+        }
+        self.pool.push(Node::Leaf(v));
+        res
+    }
+}
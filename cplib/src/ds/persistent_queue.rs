@@ -0,0 +1,150 @@
+//! 永続スタック・永続キュー
+
+
+
+/// 永続スタック
+/// 
+/// ノードプール上の連結リストで管理する。ノードの index を版として扱い、空のスタックの版は `!0` である。
+/// 
+/// - `push, pop, top, len`: `O(1)`
+pub struct PersistentStack<T> {
+    /// `(値, 次のノード, 長さ)`
+    pool: Vec<(T, usize, usize)>,
+}
+
+impl<T> PersistentStack<T> {
+    pub fn new() -> Self { Self { pool: vec![] } }
+    
+    /// 生成したノード数を返す。
+    pub fn pool_len(&self) -> usize { self.pool.len() }
+    
+    pub fn len(&self, ver: usize) -> usize { if ver == !0 { 0 } else { self.pool[ver].2 } }
+    pub fn top(&self, ver: usize) -> Option<&T> { self.pool.get(ver).map(|p| &p.0) }
+    
+    /// `ver` に `v` を積んだ版を返す。
+    pub fn push(&mut self, ver: usize, v: T) -> usize {
+        self.pool.push((v, ver, self.len(ver)+1));
+        self.pool.len()-1
+    }
+    
+    /// `ver` から先頭を取り除いた版を返す。空のとき `None` を返す。
+    pub fn pop(&self, ver: usize) -> Option<usize> { self.pool.get(ver).map(|p| p.1) }
+    
+    /// 先頭から順に返す。
+    pub fn iter(&self, mut ver: usize) -> impl Iterator<Item = &T> {
+        std::iter::from_fn(move || {
+            let (v, next, _) = self.pool.get(ver)?;
+            ver = *next;
+            Some(v)
+        })
+    }
+}
+
+
+
+/// 遅延リストのセル。空のリストは `!0` で表す。
+enum Cell<T> {
+    Cons(T, usize),
+    /// `f ++ rev(r) ++ a` を表す未評価のセル。`|r| = |f| + 1` である。
+    Rot(usize, usize, usize),
+}
+
+/// 永続キュー (banker's queue)
+/// 
+/// 前側の遅延リスト `f` と後ろ側のリスト `r` で管理し、`|r| <= |f|` を保つ。`|r| > |f|` となったとき `f ++ rev(r)` を遅延して計算する。
+/// 評価結果はノードプール上でメモ化されるため、永続的に使っても計算量は償却される。
+/// 
+/// 版は `push, pop` が返す index で、初期状態 (空) の版は `0` である。
+/// 
+/// - `push, pop, front`: amortized `O(1)`
+pub struct PersistentQueue<T: Clone> {
+    pool: Vec<Cell<T>>,
+    /// `(f, |f|, r, |r|)`
+    vers: Vec<(usize, usize, usize, usize)>,
+}
+
+impl<T: Clone> PersistentQueue<T> {
+    pub fn new() -> Self { Self { pool: vec![], vers: vec![(!0, 0, !0, 0)] } }
+    
+    /// 生成したノード数を返す。
+    pub fn pool_len(&self) -> usize { self.pool.len() + self.vers.len() }
+    
+    pub fn len(&self, ver: usize) -> usize { self.vers[ver].1 + self.vers[ver].3 }
+    pub fn is_empty(&self, ver: usize) -> bool { self.len(ver) == 0 }
+    
+    fn alloc(&mut self, c: Cell<T>) -> usize {
+        self.pool.push(c);
+        self.pool.len()-1
+    }
+    
+    /// セル `s` を評価して、`(値, 次のセル)` を返す。
+    fn force(&mut self, s: usize) -> (&T, usize) {
+        if let Cell::Rot(f, r, a) = self.pool[s] {
+            let Cell::Cons(rv, rn) = &self.pool[r] else { unreachable!() };
+            let (rv, rn) = (rv.clone(), *rn);
+            let c = if f == !0 {
+                Cell::Cons(rv, a)
+            } else {
+                let (fv, fn_) = self.force(f);
+                let fv = fv.clone();
+                let na = self.alloc(Cell::Cons(rv, a));
+                let nr = self.alloc(Cell::Rot(fn_, rn, na));
+                Cell::Cons(fv, nr)
+            };
+            self.pool[s] = c;
+        }
+        let Cell::Cons(v, next) = &self.pool[s] else { unreachable!() };
+        (v, *next)
+    }
+    
+    /// 不変条件 `|r| <= |f|` を満たすようにして、新しい版を作る。
+    fn make(&mut self, f: usize, lf: usize, r: usize, lr: usize) -> usize {
+        let v = if lr <= lf {
+            (f, lf, r, lr)
+        } else {
+            (self.alloc(Cell::Rot(f, r, !0)), lf+lr, !0, 0)
+        };
+        self.vers.push(v);
+        self.vers.len()-1
+    }
+    
+    pub fn front(&mut self, ver: usize) -> Option<&T> {
+        let f = self.vers[ver].0;
+        if f == !0 { return None; }
+        Some(self.force(f).0)
+    }
+    
+    /// `ver` の末尾に `v` を追加した版を返す。
+    pub fn push(&mut self, ver: usize, v: T) -> usize {
+        let (f, lf, r, lr) = self.vers[ver];
+        let r = self.alloc(Cell::Cons(v, r));
+        self.make(f, lf, r, lr+1)
+    }
+    
+    /// `ver` から先頭を取り除いた版を返す。空のとき `None` を返す。
+    pub fn pop(&mut self, ver: usize) -> Option<usize> {
+        let (f, lf, r, lr) = self.vers[ver];
+        if f == !0 { return None; }
+        let (_, next) = self.force(f);
+        Some(self.make(next, lf-1, r, lr))
+    }
+    
+    /// 先頭から順に並べたものを返す。
+    pub fn to_vec(&mut self, ver: usize) -> Vec<T> {
+        let (mut f, _, mut r, _) = self.vers[ver];
+        let mut res = vec![];
+        while f != !0 {
+            let (v, next) = self.force(f);
+            res.push(v.clone());
+            f = next;
+        }
+        let mid = res.len();
+        while r != !0 {
+            let Cell::Cons(v, next) = &self.pool[r] else { unreachable!() };
+            res.push(v.clone());
+            r = *next;
+        }
+        res[mid..].reverse();
+        res
+    }
+}
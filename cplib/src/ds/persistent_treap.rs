@@ -0,0 +1,252 @@
+//! 永続 RBST
+
+use std::ops::RangeBounds;
+use crate::cplib::{ds::splay_tree::SplayOp, util::func::to_bounds};
+
+
+
+/// 永続 RBST (列)
+/// 
+/// 列をノードプール上の RBST で管理する。更新系の関数は新しい根を返し、それまでの根もそのまま使える。
+/// 部分列をコピーして貼り付けるような操作も、`split` と `merge` の組み合わせで `O(log N)` で行える。
+/// 
+/// - 空列の根は `!0` である。
+/// - 演算は [`SplayTree`](crate::cplib::ds::splay_tree::SplayTree) と共通の [`SplayOp`] を使う。
+/// - 遅延の伝搬でもノードを生成するため、`get, fold` もノードを生成する。
+///   [`PersistentTreap::pool_len`] が大きくなりすぎたときは [`PersistentTreap::rebuild`] で作り直す。
+/// 
+/// # 計算量
+/// 
+/// 全て期待 `O(log N)` で、その個数のノードを生成する。
+pub struct PersistentTreap<Op: SplayOp> {
+    pool: Vec<Node<Op>>,
+    rng: u64,
+}
+
+/// `lazy, rev` の対象は自身を含まない。
+struct Node<Op: SplayOp> {
+    value: Op::Value,
+    acc: [Op::Acc; 2],
+    lazy: Option<Op::Lazy>,
+    rev: bool,
+    len: usize,
+    c: [usize; 2],
+}
+
+impl<Op: SplayOp> Clone for Node<Op> {
+    fn clone(&self) -> Self {
+        Self { value: self.value.clone(), acc: self.acc.clone(), lazy: self.lazy.clone(), rev: self.rev, len: self.len, c: self.c }
+    }
+}
+
+
+
+impl<Op: SplayOp> PersistentTreap<Op> {
+    pub fn new() -> Self { Self { pool: vec![], rng: 88172645463325252 } }
+    
+    /// 生成したノード数を返す。
+    pub fn pool_len(&self) -> usize { self.pool.len() }
+    
+    /// 全てのノードを破棄する。
+    pub fn clear(&mut self) { self.pool.clear(); }
+    
+    pub fn len(&self, root: usize) -> usize { if root == !0 { 0 } else { self.pool[root].len } }
+    
+    fn rand(&mut self) -> u64 {
+        self.rng ^= self.rng << 7;
+        self.rng ^= self.rng >> 9;
+        self.rng
+    }
+    
+    /// `c` から `len, acc` を再計算して、新しいノードとして追加する。
+    fn alloc(&mut self, mut node: Node<Op>) -> usize {
+        node.len = 1;
+        node.acc[0] = Op::to_acc(&node.value);
+        node.acc[1] = node.acc[0].clone();
+        for i in 0..2 {
+            let c = node.c[i];
+            if c == !0 { continue; }
+            let c = &self.pool[c];
+            node.len += c.len;
+            node.acc[i] = Op::prod_acc(&c.acc[i], &node.acc[i]);
+            node.acc[i^1] = Op::prod_acc(&node.acc[i^1], &c.acc[i^1]);
+        }
+        self.pool.push(node);
+        self.pool.len()-1
+    }
+    
+    /// `p` をコピーして、部分木全体に `lazy` を作用・`rev` ならば反転したノードを返す。
+    fn apply(&mut self, p: usize, lazy: Option<&Op::Lazy>, rev: bool) -> usize {
+        if p == !0 { return !0; }
+        let mut node = self.pool[p].clone();
+        if let Some(lazy) = lazy {
+            Op::act_value(&mut node.value, lazy);
+            Op::act_acc(&mut node.acc[0], lazy);
+            Op::act_acc(&mut node.acc[1], lazy);
+            if let Some(l) = node.lazy.as_mut() { Op::comp_lazy(l, lazy); } else { node.lazy = Some(lazy.clone()); }
+        }
+        if rev {
+            node.c.swap(0, 1);
+            node.acc.swap(0, 1);
+            node.rev ^= true;
+        }
+        self.pool.push(node);
+        self.pool.len()-1
+    }
+    
+    /// `lazy, rev` を子に伝搬させる。`p` が表す列は変わらないので、`p` 自体は書き換えてよい。
+    fn push(&mut self, p: usize) {
+        let lazy = self.pool[p].lazy.take();
+        let rev = std::mem::replace(&mut self.pool[p].rev, false);
+        if lazy.is_none() && !rev { return; }
+        for i in 0..2 {
+            let c = self.apply(self.pool[p].c[i], lazy.as_ref(), rev);
+            self.pool[p].c[i] = c;
+        }
+    }
+    
+    /// 列 `values` を表す木を作り、その根を返す。
+    pub fn build(&mut self, values: impl IntoIterator<Item = Op::Value>) -> usize {
+        let mut values: Vec<_> = values.into_iter().map(Some).collect();
+        self.build_rec(&mut values)
+    }
+    
+    fn build_rec(&mut self, values: &mut [Option<Op::Value>]) -> usize {
+        if values.is_empty() { return !0; }
+        let m = values.len()/2;
+        let (l, r) = values.split_at_mut(m);
+        let (x, r) = r.split_first_mut().unwrap();
+        let c = [self.build_rec(l), self.build_rec(r)];
+        let value = x.take().unwrap();
+        self.alloc(Node { acc: [Op::to_acc(&value), Op::to_acc(&value)], value, lazy: None, rev: false, len: 1, c })
+    }
+    
+    /// `l, r` を連結した列の根を返す。
+    pub fn merge(&mut self, l: usize, r: usize) -> usize {
+        if l == !0 { return r; }
+        if r == !0 { return l; }
+        let (nl, nr) = (self.pool[l].len, self.pool[r].len);
+        if (self.rand() % (nl+nr) as u64) < nl as u64 {
+            self.push(l);
+            let mut node = self.pool[l].clone();
+            node.c[1] = self.merge(node.c[1], r);
+            self.alloc(node)
+        } else {
+            self.push(r);
+            let mut node = self.pool[r].clone();
+            node.c[0] = self.merge(l, node.c[0]);
+            self.alloc(node)
+        }
+    }
+    
+    /// `root[..at], root[at..]` の根を返す。
+    pub fn split(&mut self, root: usize, at: usize) -> [usize; 2] {
+        assert!(at <= self.len(root));
+        if at == 0 { return [!0, root]; }
+        if at == self.len(root) { return [root, !0]; }
+        self.push(root);
+        let mut node = self.pool[root].clone();
+        let nl = self.len(node.c[0]);
+        if at <= nl {
+            let [l, r] = self.split(node.c[0], at);
+            node.c[0] = r;
+            [l, self.alloc(node)]
+        } else {
+            let [l, r] = self.split(node.c[1], at-nl-1);
+            node.c[1] = l;
+            [self.alloc(node), r]
+        }
+    }
+    
+    /// `[root[..l], root[l..r], root[r..]]` の根を返す。
+    fn split3(&mut self, root: usize, range: impl RangeBounds<usize>) -> [usize; 3] {
+        let [l, r] = to_bounds(range, self.len(root));
+        let [a, c] = self.split(root, r);
+        let [a, b] = self.split(a, l);
+        [a, b, c]
+    }
+    
+    pub fn get(&mut self, root: usize, mut idx: usize) -> &Op::Value {
+        assert!(idx < self.len(root), "index out of bounds (@ PersistentTreap::get)");
+        let mut p = root;
+        loop {
+            self.push(p);
+            let nl = self.len(self.pool[p].c[0]);
+            match idx.cmp(&nl) {
+                std::cmp::Ordering::Less => { p = self.pool[p].c[0]; }
+                std::cmp::Ordering::Equal => { return &self.pool[p].value; }
+                std::cmp::Ordering::Greater => { idx -= nl+1; p = self.pool[p].c[1]; }
+            }
+        }
+    }
+    
+    /// `idx` 番目に `f` を適用した列の根を返す。
+    pub fn set(&mut self, root: usize, idx: usize, f: impl FnOnce(&mut Op::Value)) -> usize {
+        assert!(idx < self.len(root), "index out of bounds (@ PersistentTreap::set)");
+        let [a, b, c] = self.split3(root, idx..idx+1);
+        let mut node = self.pool[b].clone();
+        f(&mut node.value);
+        let b = self.alloc(node);
+        let b = self.merge(b, c);
+        self.merge(a, b)
+    }
+    
+    /// `idx` 番目に `value` を挿入した列の根を返す。
+    pub fn insert(&mut self, root: usize, idx: usize, value: Op::Value) -> usize {
+        let [a, c] = self.split(root, idx);
+        let b = self.build([value]);
+        let b = self.merge(b, c);
+        self.merge(a, b)
+    }
+    
+    /// `idx` 番目を削除した列の根を返す。
+    pub fn remove(&mut self, root: usize, idx: usize) -> usize {
+        assert!(idx < self.len(root), "index out of bounds (@ PersistentTreap::remove)");
+        let [a, _, c] = self.split3(root, idx..idx+1);
+        self.merge(a, c)
+    }
+    
+    /// `root[range]` を左から畳み込んだ値と右から畳み込んだ値を返す。区間が空のとき `None` を返す。
+    pub fn fold(&mut self, root: usize, range: impl RangeBounds<usize>) -> Option<[Op::Acc; 2]> {
+        let [_, b, _] = self.split3(root, range);
+        (b != !0).then(|| self.pool[b].acc.clone())
+    }
+    
+    /// `root[range]` に `lazy` を作用させた列の根を返す。
+    pub fn act(&mut self, root: usize, range: impl RangeBounds<usize>, lazy: &Op::Lazy) -> usize {
+        let [a, b, c] = self.split3(root, range);
+        let b = self.apply(b, Some(lazy), false);
+        let b = self.merge(b, c);
+        self.merge(a, b)
+    }
+    
+    /// `root[range]` を反転させた列の根を返す。
+    pub fn reverse(&mut self, root: usize, range: impl RangeBounds<usize>) -> usize {
+        let [a, b, c] = self.split3(root, range);
+        let b = self.apply(b, None, true);
+        let b = self.merge(b, c);
+        self.merge(a, b)
+    }
+    
+    /// `root` が表す列を返す。
+    pub fn to_vec(&mut self, root: usize) -> Vec<Op::Value> {
+        let mut res = Vec::with_capacity(self.len(root));
+        let (mut stk, mut p) = (vec![], root);
+        while p != !0 || !stk.is_empty() {
+            while p != !0 { self.push(p); stk.push(p); p = self.pool[p].c[0]; }
+            let q = stk.pop().unwrap();
+            res.push(self.pool[q].value.clone());
+            p = self.pool[q].c[1];
+        }
+        res
+    }
+    
+    /// `roots` が表す列以外を破棄して作り直し、新しい根を返す。
+    /// 
+    /// 根の間でのノードの共有は失われるので、合計の長さに比例したノードを生成する。
+    pub fn rebuild(&mut self, roots: &[usize]) -> Vec<usize> {
+        let seqs: Vec<_> = roots.iter().map(|&r| self.to_vec(r)).collect();
+        self.pool.clear();
+        seqs.into_iter().map(|s| self.build(s)).collect()
+    }
+}
@@ -0,0 +1,56 @@
+//! 永続 Union Find
+
+use crate::cplib::ds::persistent_array::PersistentArray;
+
+
+
+/// 永続 Union Find (union by size)
+/// 
+/// [`PersistentArray`] の上で経路圧縮をしない Union Find を管理する。`merge` は新しい版を返し、それまでの版も使える。初期状態の版は `0` である。
+/// 
+/// - `leader`, `is_same`, `size`: `O(log^2 N)`
+/// - `merge`: `O(log^2 N)`, `O(log N)` 個のノードを生成する。
+/// 
+/// # 例題
+/// 
+/// - Persistent UnionFind: <https://judge.yosupo.jp/problem/persistent_unionfind>
+pub struct PersistentUnionFind {
+    /// `(par, size)`
+    dat: PersistentArray<(usize, usize)>,
+}
+
+impl PersistentUnionFind {
+    pub fn new(len: usize) -> Self {
+        Self { dat: PersistentArray::new((0..len).map(|i| (i, 1)).collect()) }
+    }
+    
+    pub fn len(&self) -> usize { self.dat.len() }
+    
+    pub fn leader(&self, ver: usize, mut i: usize) -> usize {
+        loop {
+            let p = self.dat.get(ver, i).0;
+            if p == i { return i; }
+            i = p;
+        }
+    }
+    
+    pub fn size(&self, ver: usize, i: usize) -> usize { self.dat.get(ver, self.leader(ver, i)).1 }
+    pub fn is_same(&self, ver: usize, i: usize, j: usize) -> bool { self.leader(ver, i) == self.leader(ver, j) }
+    
+    /// `ver` に辺 `(i, j)` を追加した版を返す。元々連結であったときは `ver` を返す。
+    pub fn merge(&mut self, ver: usize, i: usize, j: usize) -> usize {
+        let (mut old, mut new) = (self.leader(ver, i), self.leader(ver, j));
+        if old == new { return ver; }
+        let (mut so, mut sn) = (self.dat.get(ver, old).1, self.dat.get(ver, new).1);
+        if !(so <= sn) { (old, new, so, sn) = (new, old, sn, so); }
+        let ver = self.dat.set(ver, old, (new, so));
+        self.dat.set(ver, new, (new, so+sn))
+    }
+    
+    /// 版 `ver` における `res[i] = { j | leader(j) == i }`
+    pub fn groups(&self, ver: usize) -> Vec<Vec<usize>> {
+        let mut res = crate::nest![void; self.len()];
+        for i in 0..self.len() { res[self.leader(ver, i)].push(i); }
+        res
+    }
+}
@@ -0,0 +1,229 @@
+//! 疎永続遅延セグメント木
+
+use std::ops::RangeBounds;
+use crate::cplib::{ds::segtree::SegtreeOp, util::func::to_bounds};
+
+
+
+/// 疎永続遅延セグメント木
+/// 
+/// 長さ `2^60` 程度までの列を、必要になったノードのみ生成して管理する。更新系の関数は新しい根を返し、それまでの根もそのまま使える。
+/// 
+/// - 初期状態の根は `0` である。
+/// - 葉の初期値は `Op::id_value()` であるが、[`PointerSegtree::with_leaf`] で指定できる。区間長を `Value` に持たせるときに使う。
+/// - `Op::BEATS` には対応していない。
+/// 
+/// # 計算量
+/// 
+/// - `get, fold, max_right, min_left`: `O(log N)`, ノードを生成しない。
+/// - `set, apply`: `O(log N)`, `O(log N)` 個のノードを生成する。
+pub struct PointerSegtree<Op: SegtreeOp> {
+    depth: usize,
+    /// `def[d]` = 高さ `d` の初期状態の部分木の値
+    def: Vec<Op::Value>,
+    pool: Vec<Node<Op>>,
+}
+
+/// `c[i] == !0` のとき、子は初期状態の部分木である。`lazy` の対象は自身を含まない。
+struct Node<Op: SegtreeOp> {
+    value: Op::Value,
+    lazy: Option<Op::Lazy>,
+    c: [usize; 2],
+}
+
+impl<Op: SegtreeOp> Clone for Node<Op> {
+    fn clone(&self) -> Self {
+        Self { value: self.value.clone(), lazy: self.lazy.clone(), c: self.c }
+    }
+}
+
+
+
+impl<Op: SegtreeOp> PointerSegtree<Op> {
+    pub fn new(len: usize) -> Self { Self::with_leaf(len, Op::id_value()) }
+    
+    /// 葉の初期値を `leaf` とする。
+    pub fn with_leaf(len: usize, leaf: Op::Value) -> Self {
+        let depth = len.max(1).next_power_of_two().trailing_zeros() as usize;
+        let mut def = vec![leaf];
+        for d in 0..depth { def.push(Op::prod_value(&def[d], &def[d])); }
+        let pool = vec![Node { value: def[depth].clone(), lazy: None, c: [!0; 2] }];
+        Self { depth, def, pool }
+    }
+    
+    /// 最下層の長さを返す。これは [`PointerSegtree::new`] で指定した長さと異なる可能性がある。
+    pub fn len(&self) -> usize { 1 << self.depth }
+    
+    /// 生成したノード数を返す。
+    pub fn pool_len(&self) -> usize { self.pool.len() }
+    
+    /// 全てのノードを破棄して、初期状態の根 `0` のみにする。
+    pub fn clear(&mut self) { self.pool.truncate(1); }
+    
+    pub fn get(&self, root: usize, i: usize) -> Op::Value {
+        assert!(i < self.len());
+        let (mut p, mut lazy) = (root, vec![]);
+        for d in (0..self.depth).rev() {
+            if p == !0 { break; }
+            if let Some(l) = &self.pool[p].lazy { lazy.push(l); }
+            p = self.pool[p].c[i >> d & 1];
+        }
+        let mut res = self.value(p, 0).clone();
+        for l in lazy.into_iter().rev() { act_value::<Op>(&mut res, l); }
+        res
+    }
+    
+    /// `root` の `i` 番目に `f` を作用させた木の根を返す。
+    pub fn set(&mut self, root: usize, i: usize, f: impl FnOnce(&mut Op::Value)) -> usize {
+        assert!(i < self.len());
+        self._set(root, self.depth, i, f)
+    }
+    
+    /// `root` の `range` に `lazy` を作用させた木の根を返す。
+    pub fn apply(&mut self, root: usize, range: impl RangeBounds<usize>, lazy: Op::Lazy) -> usize {
+        let [l, r] = to_bounds(range, self.len());
+        if l == r { return root; }
+        self._apply(root, self.depth, 0, l, r, &lazy)
+    }
+    
+    pub fn fold(&self, root: usize, range: impl RangeBounds<usize>) -> Op::Value {
+        let [l, r] = to_bounds(range, self.len());
+        if l == r { return Op::id_value(); }
+        self._fold(root, self.depth, 0, l, r)
+    }
+    
+    /// `f(fold(l..r)) == true && f(fold(l..r+1)) == false` である `r` を一つ返す。
+    /// ただし `f(fold(l..l)) == true`, `f(fold(l..len+1)) == false` であるとする。
+    pub fn max_right(&self, root: usize, l: usize, f: impl Fn(&Op::Value) -> bool) -> usize {
+        assert!(l <= self.len());
+        let mut acc = Op::id_value();
+        self._max_right(root, self.depth, 0, l, None, &mut acc, &f).unwrap_or(self.len())
+    }
+    
+    /// `f(fold(l-1..r)) == false && f(fold(l..r)) == true` である `l` を一つ返す。
+    /// ただし `f(fold(-1..r)) == false`, `f(fold(r..r)) == true` であるとする。
+    pub fn min_left(&self, root: usize, r: usize, f: impl Fn(&Op::Value) -> bool) -> usize {
+        assert!(r <= self.len());
+        let mut acc = Op::id_value();
+        self._min_left(root, self.depth, 0, r, None, &mut acc, &f).unwrap_or(0)
+    }
+    
+    
+    
+    fn value(&self, p: usize, d: usize) -> &Op::Value {
+        if p == !0 { &self.def[d] } else { &self.pool[p].value }
+    }
+    
+    /// 高さ `d` のノード `p` を複製して、その index を返す。
+    fn copy(&mut self, p: usize, d: usize) -> usize {
+        let node = if p == !0 { Node { value: self.def[d].clone(), lazy: None, c: [!0; 2] } } else { self.pool[p].clone() };
+        self.pool.push(node);
+        self.pool.len()-1
+    }
+    
+    /// 高さ `d` のノード `p` に `lazy` を作用させたノードを作り、その index を返す。
+    fn act(&mut self, p: usize, d: usize, lazy: &Op::Lazy) -> usize {
+        let q = self.copy(p, d);
+        act_value::<Op>(&mut self.pool[q].value, lazy);
+        if d != 0 { comp_lazy::<Op>(&mut self.pool[q].lazy, lazy); }
+        q
+    }
+    
+    /// 複製済みのノード `q` の `lazy` を子に伝搬させる。子は複製される。
+    fn push(&mut self, q: usize, d: usize) {
+        let Some(lazy) = self.pool[q].lazy.take() else { return };
+        for i in 0..2 { self.pool[q].c[i] = self.act(self.pool[q].c[i], d-1, &lazy); }
+    }
+    
+    fn update(&mut self, q: usize, d: usize) {
+        let [l, r] = self.pool[q].c;
+        self.pool[q].value = Op::prod_value(self.value(l, d-1), self.value(r, d-1));
+    }
+    
+    fn _set(&mut self, p: usize, d: usize, i: usize, f: impl FnOnce(&mut Op::Value)) -> usize {
+        let q = self.copy(p, d);
+        if d == 0 { f(&mut self.pool[q].value); return q; }
+        self.push(q, d);
+        let b = i >> d-1 & 1;
+        self.pool[q].c[b] = self._set(self.pool[q].c[b], d-1, i, f);
+        self.update(q, d);
+        q
+    }
+    
+    /// ノード `p` は `lo..lo+2^d` を担当する。
+    fn _apply(&mut self, p: usize, d: usize, lo: usize, l: usize, r: usize, lazy: &Op::Lazy) -> usize {
+        let hi = lo + (1 << d);
+        if r <= lo || hi <= l { return p; }
+        if l <= lo && hi <= r { return self.act(p, d, lazy); }
+        let q = self.copy(p, d);
+        self.push(q, d);
+        let m = lo + (1 << d-1);
+        self.pool[q].c[0] = self._apply(self.pool[q].c[0], d-1, lo, l, r, lazy);
+        self.pool[q].c[1] = self._apply(self.pool[q].c[1], d-1, m, l, r, lazy);
+        self.update(q, d);
+        q
+    }
+    
+    /// 共通部分が空でない区間について呼ぶこと。
+    fn _fold(&self, p: usize, d: usize, lo: usize, l: usize, r: usize) -> Op::Value {
+        let (hi, m) = (lo + (1 << d), lo + (1 << d >> 1));
+        if l <= lo && hi <= r { return self.value(p, d).clone(); }
+        let (c, lazy) = if p == !0 { ([!0; 2], None) } else { (self.pool[p].c, self.pool[p].lazy.as_ref()) };
+        let mut res = if r <= m {
+            self._fold(c[0], d-1, lo, l, r)
+        } else if m <= l {
+            self._fold(c[1], d-1, m, l, r)
+        } else {
+            Op::prod_value(&self._fold(c[0], d-1, lo, l, r), &self._fold(c[1], d-1, m, l, r))
+        };
+        if let Some(lazy) = lazy { act_value::<Op>(&mut res, lazy); }
+        res
+    }
+    
+    /// `p` の先祖から受けている作用 `up` を合成して、子が受ける作用を返す。
+    fn down(&self, p: usize, up: Option<Op::Lazy>) -> ([usize; 2], Option<Op::Lazy>) {
+        if p == !0 { return ([!0; 2], up); }
+        let Some(mut lazy) = self.pool[p].lazy.clone() else { return (self.pool[p].c, up); };
+        if let Some(up) = &up { Op::prod_lazy(&mut lazy, up); }
+        (self.pool[p].c, Some(lazy))
+    }
+    
+    fn _max_right(&self, p: usize, d: usize, lo: usize, l: usize, up: Option<Op::Lazy>, acc: &mut Op::Value, f: &impl Fn(&Op::Value) -> bool) -> Option<usize> {
+        if lo + (1 << d) <= l { return None; }
+        if l <= lo {
+            let mut v = self.value(p, d).clone();
+            if let Some(up) = &up { act_value::<Op>(&mut v, up); }
+            let tmp = Op::prod_value(acc, &v);
+            if f(&tmp) { *acc = tmp; return None; }
+            if d == 0 { return Some(lo); }
+        }
+        let (c, up) = self.down(p, up);
+        if let Some(res) = self._max_right(c[0], d-1, lo, l, up.clone(), acc, f) { return Some(res); }
+        self._max_right(c[1], d-1, lo + (1 << d-1), l, up, acc, f)
+    }
+    
+    fn _min_left(&self, p: usize, d: usize, lo: usize, r: usize, up: Option<Op::Lazy>, acc: &mut Op::Value, f: &impl Fn(&Op::Value) -> bool) -> Option<usize> {
+        if r <= lo { return None; }
+        if lo + (1 << d) <= r {
+            let mut v = self.value(p, d).clone();
+            if let Some(up) = &up { act_value::<Op>(&mut v, up); }
+            let tmp = Op::prod_value(&v, acc);
+            if f(&tmp) { *acc = tmp; return None; }
+            if d == 0 { return Some(lo+1); }
+        }
+        let (c, up) = self.down(p, up);
+        if let Some(res) = self._min_left(c[1], d-1, lo + (1 << d-1), r, up.clone(), acc, f) { return Some(res); }
+        self._min_left(c[0], d-1, lo, r, up, acc, f)
+    }
+}
+
+
+
+fn act_value<Op: SegtreeOp>(value: &mut Op::Value, lazy: &Op::Lazy) {
+    let res = Op::act_value(value, lazy);
+    debug_assert!(res, "PointerSegtree does not support SegtreeOp::BEATS");
+}
+
+fn comp_lazy<Op: SegtreeOp>(lazy: &mut Option<Op::Lazy>, ad: &Op::Lazy) {
+    if let Some(lazy) = lazy { Op::prod_lazy(lazy, ad); } else { *lazy = Some(ad.clone()); }
+}
@@ -0,0 +1,192 @@
+use std::{collections::BTreeMap, fmt::Debug, ops::RangeBounds};
+use crate::cplib::util::func::to_bounds;
+
+pub use {range_set::RangeSet, range_map::RangeMap};
+
+
+mod range_set {
+    use super::*;
+    
+    /// 半開区間の集合を管理する。隣接・重複する区間は併合される。
+    /// 
+    /// 半開区間なので `usize::MAX` は入れられないことに注意。
+    /// 
+    /// # 計算量
+    /// 
+    /// `insert_range, erase_range` は併合・削除される区間の個数を `k` として `O((k+1) log N)` (amortized `O(log N)`)
+    #[derive(Clone, PartialEq, Eq)]
+    pub struct RangeSet {
+        /// `l -> r`
+        map: BTreeMap<usize, usize>,
+        total: usize,
+    }
+    
+    impl RangeSet {
+        pub fn new() -> Self { Self { map: BTreeMap::new(), total: 0 } }
+        /// 区間の個数を返す。
+        pub fn len(&self) -> usize { self.map.len() }
+        pub fn is_empty(&self) -> bool { self.map.is_empty() }
+        /// 被覆されている長さの合計を返す。
+        pub fn total_len(&self) -> usize { self.total }
+        pub fn clear(&mut self) { self.map.clear(); self.total = 0; }
+        
+        pub fn contains(&self, x: usize) -> bool { self.covering(x).is_some() }
+        
+        /// `x` を含む区間 `[l, r)` を返す。
+        pub fn covering(&self, x: usize) -> Option<(usize, usize)> {
+            self.map.range(..=x).next_back().filter(|e| x < *e.1).map(|(&l, &r)| (l, r))
+        }
+        
+        /// `from` 以上で被覆されていない最小の値を返す。
+        pub fn mex(&self, from: usize) -> usize {
+            self.covering(from).map_or(from, |e| e.1)
+        }
+        
+        /// `x` を追加する。新たに追加されたとき `true` を返す。
+        pub fn insert(&mut self, x: usize) -> bool { self.insert_range(x..x+1) != 0 }
+        
+        /// `x` を削除する。削除されたとき `true` を返す。
+        pub fn remove(&mut self, x: usize) -> bool { self.erase_range(x..x+1) != 0 }
+        
+        /// `range` を追加して、新たに被覆された長さを返す。
+        pub fn insert_range(&mut self, range: impl RangeBounds<usize>) -> usize {
+            let [mut l, mut r] = to_bounds(range, usize::MAX);
+            if l == r { return 0; }
+            let mut removed = 0;
+            if let Some((&a, &b)) = self.map.range(..l).next_back() {
+                if l <= b { self.map.remove(&a); removed += b-a; l = a; r = r.max(b); }
+            }
+            while let Some((&a, &b)) = self.map.range(l..=r).next() {
+                self.map.remove(&a); removed += b-a; r = r.max(b);
+            }
+            self.map.insert(l, r);
+            let res = r-l-removed;
+            self.total += res;
+            res
+        }
+        
+        /// `range` を削除して、削除された長さを返す。
+        pub fn erase_range(&mut self, range: impl RangeBounds<usize>) -> usize {
+            let [l, r] = to_bounds(range, usize::MAX);
+            if l == r { return 0; }
+            let mut res = 0;
+            if let Some((&a, &b)) = self.map.range(..l).next_back() {
+                if l < b {
+                    self.map.insert(a, l);
+                    if r < b { self.map.insert(r, b); }
+                    res += b.min(r)-l;
+                }
+            }
+            while let Some((&a, &b)) = self.map.range(l..r).next() {
+                self.map.remove(&a);
+                if r < b { self.map.insert(r, b); }
+                res += b.min(r)-a;
+            }
+            self.total -= res;
+            res
+        }
+        
+        /// 区間 `[l, r)` を昇順に返す。
+        pub fn iter(&self) -> impl DoubleEndedIterator<Item = (usize, usize)> + '_ {
+            self.map.iter().map(|(&l, &r)| (l, r))
+        }
+    }
+    
+    impl Debug for RangeSet {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_set().entries(self.iter().map(|(l, r)| l..r)).finish()
+        }
+    }
+}
+
+
+mod range_map {
+    use super::*;
+    
+    /// 半開区間に値を割り当てる (いわゆる ODT, Chtholly Tree)。隣接する区間の値が等しいとき併合される。
+    /// 
+    /// # 計算量
+    /// 
+    /// `assign, erase_range` は削除される区間の個数を `k` として `O((k+1) log N)` (amortized `O(log N)`)
+    #[derive(Clone, PartialEq, Eq)]
+    pub struct RangeMap<T: Clone + PartialEq> {
+        /// `l -> (r, value)`
+        map: BTreeMap<usize, (usize, T)>,
+    }
+    
+    impl<T: Clone + PartialEq> RangeMap<T> {
+        pub fn new() -> Self { Self { map: BTreeMap::new() } }
+        /// 区間の個数を返す。
+        pub fn len(&self) -> usize { self.map.len() }
+        pub fn is_empty(&self) -> bool { self.map.is_empty() }
+        pub fn clear(&mut self) { self.map.clear(); }
+        
+        pub fn get(&self, x: usize) -> Option<&T> { self.covering(x).map(|e| e.2) }
+        
+        /// `x` を含む区間 `[l, r)` とその値を返す。
+        pub fn covering(&self, x: usize) -> Option<(usize, usize, &T)> {
+            self.map.range(..=x).next_back().filter(|e| x < e.1.0).map(|(&l, (r, v))| (l, *r, v))
+        }
+        
+        /// `x` をまたぐ区間があれば `x` で分割する。
+        fn split(&mut self, x: usize) {
+            let Some((_, (r, v))) = self.map.range_mut(..x).next_back() else { return; };
+            if x < *r {
+                let right = (*r, v.clone());
+                *r = x;
+                self.map.insert(x, right);
+            }
+        }
+        
+        /// `range` に割り当てられた値を削除する。
+        pub fn erase_range(&mut self, range: impl RangeBounds<usize>) {
+            let [l, r] = to_bounds(range, usize::MAX);
+            if l == r { return; }
+            self.split(l); self.split(r);
+            while let Some(&a) = self.map.range(l..r).next().map(|e| e.0) { self.map.remove(&a); }
+        }
+        
+        /// `range` に `value` を割り当てる。
+        pub fn assign(&mut self, range: impl RangeBounds<usize>, value: T) {
+            let [mut l, mut r] = to_bounds(range, usize::MAX);
+            if l == r { return; }
+            self.erase_range(l..r);
+            if let Some((&a, (b, v))) = self.map.range(..l).next_back() {
+                if *b == l && *v == value { l = a; }
+            }
+            if let Some((b, v)) = self.map.get(&r) {
+                if *v == value { let b = *b; self.map.remove(&r); r = b; }
+            }
+            self.map.insert(l, (r, value));
+        }
+        
+        /// `range` と交差する区間を `range` で切り取って、`f(l, r, &mut value)` を順に呼ぶ。
+        /// 
+        /// 値を書き換えても隣接する区間との併合は行われない。
+        pub fn modify_range(&mut self, range: impl RangeBounds<usize>, mut f: impl FnMut(usize, usize, &mut T)) {
+            let [l, r] = to_bounds(range, usize::MAX);
+            if l == r { return; }
+            self.split(l); self.split(r);
+            for (&a, (b, v)) in self.map.range_mut(l..r) { f(a, *b, v); }
+        }
+        
+        /// `range` と交差する区間を `range` で切り取って、`(l, r, &value)` を昇順に返す。
+        pub fn range(&self, range: impl RangeBounds<usize>) -> impl Iterator<Item = (usize, usize, &T)> + '_ {
+            let [l, r] = to_bounds(range, usize::MAX);
+            let first = self.covering(l).filter(|e| e.0 < l && l < r);
+            let rest = self.map.range(l..r.max(l)).map(|(&a, (b, v))| (a, *b, v));
+            first.into_iter().chain(rest).map(move |(a, b, v)| (a.max(l), b.min(r), v))
+        }
+        
+        /// 区間 `[l, r)` とその値を昇順に返す。
+        pub fn iter(&self) -> impl DoubleEndedIterator<Item = (usize, usize, &T)> + '_ {
+            self.map.iter().map(|(&l, (r, v))| (l, *r, v))
+        }
+    }
+    
+    impl<T: Clone + PartialEq + Debug> Debug for RangeMap<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_map().entries(self.iter().map(|(l, r, v)| (l..r, v))).finish()
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! [`RollbackUnionFind`], [`OfflineDynamicConnectivity`]
+
+use std::collections::HashMap;
+use crate::cplib::ds::unionfind::Group;
+
+
+
+/// Potentialized Union Find (union by size, rollback)
+/// 
+/// 経路圧縮をしない代わりに、`merge` を新しい順に取り消すことができる。Potential として群 [`Group`] が乗る。
+/// 
+/// - `leader`, `merge`: `O(log N)`
+/// - `undo`: `O(1)`
+pub struct RollbackUnionFind<Op: Group> {
+    par: Vec<usize>,
+    size: Vec<usize>,
+    /// `diff[i] = P[i] - P[par[i]]`
+    diff: Vec<Op::T>,
+    count: usize,
+    /// `merge` の履歴 `(new, old)`。連結成分が変化しなかった場合は `(!0, !0)`
+    hist: Vec<(usize, usize)>,
+}
+
+impl<Op: Group> RollbackUnionFind<Op> {
+    pub fn new(len: usize) -> Self {
+        Self { par: (0..len).collect(), size: vec![1; len], diff: vec![Op::e(); len], count: len, hist: vec![] }
+    }
+    
+    pub fn len(&self) -> usize { self.par.len() }
+    
+    /// 連結成分の個数を返す。
+    pub fn count(&self) -> usize { self.count }
+    
+    pub fn leader(&self, mut i: usize) -> usize {
+        while self.par[i] != i { i = self.par[i]; }
+        i
+    }
+    
+    pub fn size(&self, i: usize) -> usize { self.size[self.leader(i)] }
+    pub fn is_same(&self, i: usize, j: usize) -> bool { self.leader(i) == self.leader(j) }
+    
+    /// `P[i] - P[leader(i)]` を返す。
+    fn potential(&self, mut i: usize) -> Op::T {
+        let mut res = Op::e();
+        while self.par[i] != i { res = Op::add(&res, &self.diff[i]); i = self.par[i]; }
+        res
+    }
+    
+    /// `P[i] - P[j]` を返す。
+    /// 
+    /// # Panics
+    /// 
+    /// if not `is_same(i, j)`
+    pub fn diff(&self, i: usize, j: usize) -> Op::T {
+        assert!(self.is_same(i, j));
+        Op::sub(&self.potential(i), &self.potential(j))
+    }
+    
+    /// `P[i] - P[j] = w` となるよう辺を追加する。返り値は [`crate::ds::unionfind::UnionFind::merge`] と同じ。
+    /// 
+    /// 連結成分が変化しなくても履歴に積まれるので、`undo` 1 回で取り消せる。
+    pub fn merge(&mut self, i: usize, j: usize, w: Op::T) -> (usize, usize) {
+        let (mut old, mut new) = (self.leader(i), self.leader(j));
+        let mut w = Op::add(&Op::add(&Op::inv(&self.potential(i)), &w), &self.potential(j));
+        if old == new {
+            self.hist.push((!0, !0));
+            return if w == Op::e() { (old, !0) } else { (!0, !0) };
+        }
+        if !(self.size[old] <= self.size[new]) { (old, new) = (new, old); w = Op::inv(&w); }
+        self.par[old] = new;
+        self.diff[old] = w;
+        self.size[new] += self.size[old];
+        self.count -= 1;
+        self.hist.push((new, old));
+        (new, old)
+    }
+    
+    /// 現在の履歴の長さを返す。[`RollbackUnionFind::rollback`] に渡すと、この時点に戻せる。
+    pub fn snapshot(&self) -> usize { self.hist.len() }
+    
+    /// 最後の `merge` を取り消す。取り消す `merge` が無いとき `false` を返す。
+    pub fn undo(&mut self) -> bool {
+        let Some((new, old)) = self.hist.pop() else { return false; };
+        if new != !0 {
+            self.par[old] = old;
+            self.diff[old] = Op::e();
+            self.size[new] -= self.size[old];
+            self.count += 1;
+        }
+        true
+    }
+    
+    /// 履歴の長さが `snapshot` になるまで `undo` する。
+    pub fn rollback(&mut self, snapshot: usize) {
+        while snapshot < self.hist.len() { self.undo(); }
+    }
+}
+
+impl<Op: Group> Clone for RollbackUnionFind<Op> {
+    fn clone(&self) -> Self {
+        Self { par: self.par.clone(), size: self.size.clone(), diff: self.diff.clone(), count: self.count, hist: self.hist.clone() }
+    }
+}
+
+
+
+/// オフライン動的連結性
+/// 
+/// 辺の追加・削除とクエリを先読みし、時間軸上のセグメント木と [`RollbackUnionFind`] で処理する。
+/// 
+/// - `solve`: `O(Q log Q log N)`
+/// 
+/// # Usage
+/// 
+/// `link`, `cut`, `query` を時系列順に呼び、`solve(f)` で各クエリ時点の状態を `f(query_id, &uf)` として受け取る。
+pub struct OfflineDynamicConnectivity {
+    n: usize,
+    /// クエリ数
+    time: usize,
+    /// 存在している辺の追加時刻
+    open: HashMap<(usize, usize), Vec<usize>>,
+    /// 辺 `(u, v)` がクエリ `l..r` の間存在する
+    edges: Vec<(usize, usize, usize, usize)>,
+}
+
+impl OfflineDynamicConnectivity {
+    pub fn new(n: usize) -> Self {
+        Self { n, time: 0, open: HashMap::new(), edges: vec![] }
+    }
+    
+    /// 辺 `(u, v)` を追加する。多重辺も可。
+    pub fn link(&mut self, u: usize, v: usize) {
+        self.open.entry((u.min(v), u.max(v))).or_default().push(self.time);
+    }
+    
+    /// 辺 `(u, v)` を削除する。
+    /// 
+    /// # Panics
+    /// 
+    /// 辺 `(u, v)` が存在しないとき。
+    pub fn cut(&mut self, u: usize, v: usize) {
+        let (u, v) = (u.min(v), u.max(v));
+        let l = self.open.get_mut(&(u, v)).and_then(|s| s.pop()).expect("edge does not exist");
+        if l != self.time { self.edges.push((u, v, l, self.time)); }
+    }
+    
+    /// 現在時点でのクエリを追加して、その番号を返す。
+    pub fn query(&mut self) -> usize {
+        self.time += 1;
+        self.time-1
+    }
+    
+    /// 各クエリ `q` について、時刻順に `f(q, &uf)` を呼ぶ。
+    pub fn solve(mut self, mut f: impl FnMut(usize, &RollbackUnionFind<()>)) {
+        let q = self.time;
+        if q == 0 { return; }
+        for (&(u, v), s) in &self.open {
+            for &l in s { if l != q { self.edges.push((u, v, l, q)); } }
+        }
+        let sz = q.next_power_of_two();
+        let mut seg = vec![vec![]; 2*sz];
+        for &(u, v, l, r) in &self.edges {
+            let (mut l, mut r) = (l+sz, r+sz);
+            while l < r {
+                if l&1 == 1 { seg[l].push((u, v)); l += 1; }
+                if r&1 == 1 { r -= 1; seg[r].push((u, v)); }
+                l >>= 1; r >>= 1;
+            }
+        }
+        
+        let mut uf = RollbackUnionFind::<()>::new(self.n);
+        let mut dfs = vec![(1, true)];
+        while let Some((i, is_in)) = dfs.pop() {
+            if !is_in { uf.rollback(uf.snapshot() - seg[i].len()); continue; }
+            if sz <= i && q <= i-sz { continue; }
+            for &(u, v) in &seg[i] { uf.merge(u, v, ()); }
+            dfs.push((i, false));
+            if sz <= i { f(i-sz, &uf); } else { dfs.push((2*i+1, true)); dfs.push((2*i, true)); }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! 2 次元セグメント木
+
+use std::ops::RangeBounds;
+use crate::cplib::{ds::segtree::SegtreeOp, util::func::to_bounds};
+
+
+
+/// 2 次元セグメント木
+/// 
+/// 1 点更新、長方形領域の積を計算できる。`Op::prod_value` は可換である必要がある。`Lazy` は使わない。
+/// 
+/// - `set`, `fold`: `O(log H log W)`
+/// - 空間: `O(HW)`
+pub struct Segtree2D<Op: SegtreeOp> {
+    h: usize,
+    w: usize,
+    /// `tree[2w*x + y]`
+    tree: Vec<Op::Value>,
+}
+
+impl<Op: SegtreeOp> Segtree2D<Op> {
+    pub fn new(h: usize, w: usize) -> Self {
+        Self { h, w, tree: vec![Op::id_value(); 4*h*w] }
+    }
+    
+    pub fn from_fn(h: usize, w: usize, mut f: impl FnMut(usize, usize) -> Op::Value) -> Self {
+        let mut seg = Self::new(h, w);
+        for i in 0..h { for j in 0..w { let k = seg.idx(h+i, w+j); seg.tree[k] = f(i, j); } }
+        for x in h..2*h { for y in (1..w).rev() { seg.update_row(x, y); } }
+        for x in (1..h).rev() { for y in 1..2*w { seg.update_col(x, y); } }
+        seg
+    }
+    
+    /// `(H, W)` を返す。
+    pub fn len(&self) -> (usize, usize) { (self.h, self.w) }
+    
+    pub fn get(&self, i: usize, j: usize) -> &Op::Value {
+        assert!(i < self.h && j < self.w);
+        &self.tree[self.idx(self.h+i, self.w+j)]
+    }
+    
+    pub fn set<T>(&mut self, i: usize, j: usize, f: impl FnOnce(&mut Op::Value) -> T) -> T {
+        assert!(i < self.h && j < self.w);
+        let (x, mut y) = (self.h+i, self.w+j);
+        let k = self.idx(x, y);
+        let res = f(&mut self.tree[k]);
+        while { y >>= 1; y != 0 } { self.update_row(x, y); }
+        let mut x = x;
+        while { x >>= 1; x != 0 } {
+            y = self.w+j;
+            while y != 0 { self.update_col(x, y); y >>= 1; }
+        }
+        res
+    }
+    
+    /// `[x1, x2) × [y1, y2)` の積を返す。
+    pub fn fold(&self, xr: impl RangeBounds<usize>, yr: impl RangeBounds<usize>) -> Op::Value {
+        let [mut l, mut r] = to_bounds(xr, self.h).map(|v| v+self.h);
+        let [yl, yr] = to_bounds(yr, self.w);
+        let mut res = Op::id_value();
+        if yl == yr { return res; }
+        while l < r {
+            if l&1 == 1 { res = Op::prod_value(&res, &self.fold_row(l, yl, yr)); l += 1; }
+            if r&1 == 1 { r -= 1; res = Op::prod_value(&res, &self.fold_row(r, yl, yr)); }
+            l >>= 1; r >>= 1;
+        }
+        res
+    }
+    
+    fn idx(&self, x: usize, y: usize) -> usize { 2*self.w*x + y }
+    
+    fn fold_row(&self, x: usize, yl: usize, yr: usize) -> Op::Value {
+        let [mut l, mut r] = [yl, yr].map(|v| self.idx(x, v+self.w));
+        let base = self.idx(x, 0);
+        let mut res = Op::id_value();
+        while l < r {
+            if (l-base)&1 == 1 { res = Op::prod_value(&res, &self.tree[l]); l += 1; }
+            if (r-base)&1 == 1 { r -= 1; res = Op::prod_value(&res, &self.tree[r]); }
+            l = base + (l-base)/2; r = base + (r-base)/2;
+        }
+        res
+    }
+    
+    /// 行 `x` の `y` を子 `2y, 2y+1` から計算する。
+    fn update_row(&mut self, x: usize, y: usize) {
+        let k = self.idx(x, 0);
+        self.tree[k+y] = Op::prod_value(&self.tree[k+2*y], &self.tree[k+2*y+1]);
+    }
+    
+    /// 行 `x` の `y` を行 `2x, 2x+1` から計算する。
+    fn update_col(&mut self, x: usize, y: usize) {
+        let (k, l, r) = (self.idx(x, y), self.idx(2*x, y), self.idx(2*x+1, y));
+        self.tree[k] = Op::prod_value(&self.tree[l], &self.tree[r]);
+    }
+}
+
+impl<Op: SegtreeOp> Clone for Segtree2D<Op> {
+    fn clone(&self) -> Self {
+        Self { h: self.h, w: self.w, tree: self.tree.clone() }
+    }
+}
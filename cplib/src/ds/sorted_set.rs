@@ -0,0 +1,90 @@
+use std::fmt::Debug;
+
+use crate::cplib::ds::{fenwick::Fenwick, unionfind::AddI64};
+
+
+/// 値の候補を先読みした、重複を許す順序付き集合
+/// 
+/// 値の候補 (universe) を座標圧縮して [`Fenwick`] で個数を管理する。候補の個数を `U` として、各操作は `O(log U)` である。
+/// 
+/// - 候補にない値は追加できない。[`Sorted::add`] は `false` を返す。
+/// - `rank, rank_le, lower_bound, upper_bound, prev, next` の引数は候補になくてもよい。
+pub struct Sorted<T: Ord> {
+    fw: Fenwick<AddI64>,
+    cnt: Vec<usize>,
+    order: Vec<T>,
+    len: usize,
+}
+
+impl<T: Ord> Sorted<T> {
+    /// 値の候補を `v` とする。
+    pub fn new(mut v: Vec<T>) -> Self {
+        v.sort(); v.dedup();
+        Self { fw: Fenwick::new(v.len()), cnt: vec![0; v.len()], order: v, len: 0 }
+    }
+    
+    pub fn len(&self) -> usize { self.len }
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+    
+    /// `x` を `n` 個追加する (`n < 0` のときは削除する)。
+    /// 
+    /// `x` が候補にないとき、または削除後の個数が負になるとき、何もせず `false` を返す。
+    pub fn add(&mut self, x: &T, n: i64) -> bool {
+        let Ok(i) = self.order.binary_search(x) else { return false; };
+        let Some(c) = self.cnt[i].checked_add_signed(n as isize) else { return false; };
+        self.cnt[i] = c;
+        self.len = (self.len as i64 + n) as usize;
+        self.fw.add(i, &n);
+        true
+    }
+    
+    /// `x` の個数を返す。
+    pub fn count(&self, x: &T) -> usize {
+        self.order.binary_search(x).map_or(0, |i| self.cnt[i])
+    }
+    
+    /// `x` 未満の値の個数を返す。
+    pub fn rank(&self, x: &T) -> usize {
+        self.fw.sum(self.order.partition_point(|y| y < x)) as usize
+    }
+    
+    /// `x` 以下の値の個数を返す。
+    pub fn rank_le(&self, x: &T) -> usize {
+        self.fw.sum(self.order.partition_point(|y| y <= x)) as usize
+    }
+    
+    /// 小さい方から `k` 番目 (0-indexed) の値を返す。
+    pub fn nth(&self, k: usize) -> Option<&T> {
+        if k >= self.len { return None; }
+        Some(&self.order[self.fw.lower_bound(&(k as i64 + 1))])
+    }
+    
+    /// `x` 以上の最小の値を返す。
+    pub fn lower_bound(&self, x: &T) -> Option<&T> { self.nth(self.rank(x)) }
+    
+    /// `x` より大きい最小の値を返す。
+    pub fn upper_bound(&self, x: &T) -> Option<&T> { self.nth(self.rank_le(x)) }
+    
+    /// `x` 以下の最大の値を返す。
+    pub fn prev(&self, x: &T) -> Option<&T> {
+        let k = self.rank_le(x);
+        if k == 0 { None } else { self.nth(k-1) }
+    }
+    
+    /// `x` 以上の最小の値を返す。
+    pub fn next(&self, x: &T) -> Option<&T> { self.nth(self.rank(x)) }
+    
+    pub fn min(&self) -> Option<&T> { self.nth(0) }
+    pub fn max(&self) -> Option<&T> { if self.len == 0 { None } else { self.nth(self.len-1) } }
+    
+    /// 値を重複を含めて昇順に返す。`O(U + len)`
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        self.order.iter().zip(&self.cnt).flat_map(|(x, &c)| std::iter::repeat_n(x, c))
+    }
+}
+
+impl<T: Ord + Debug> Debug for Sorted<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,86 @@
+//! [`SparseTable`], [`DisjointSparseTable`]
+
+use std::ops::RangeBounds;
+use crate::cplib::{ds::segtree::SegtreeOp, util::func::to_bounds};
+
+
+
+/// Sparse Table
+/// 
+/// 静的な列の区間積を `O(1)` で計算する。`Op::prod_value` は冪等 (`x*x == x`) である必要がある。`Lazy` は使わない。
+/// 
+/// - 構築: `O(N log N)`
+/// - `fold`: `O(1)`
+pub struct SparseTable<Op: SegtreeOp> {
+    /// `dat[k][i] = prod(A[i..i+2^k])`
+    dat: Vec<Vec<Op::Value>>,
+}
+
+impl<Op: SegtreeOp> SparseTable<Op> {
+    pub fn len(&self) -> usize { self.dat[0].len() }
+    
+    pub fn fold(&self, range: impl RangeBounds<usize>) -> Op::Value {
+        let [l, r] = to_bounds(range, self.len());
+        if l == r { return Op::id_value(); }
+        let k = (r-l).ilog2() as usize;
+        Op::prod_value(&self.dat[k][l], &self.dat[k][r-(1<<k)])
+    }
+}
+
+impl<Op: SegtreeOp> FromIterator<Op::Value> for SparseTable<Op> {
+    fn from_iter<T: IntoIterator<Item = Op::Value>>(iter: T) -> Self {
+        let mut dat = vec![iter.into_iter().collect::<Vec<_>>()];
+        let n = dat[0].len();
+        for k in 1..n.max(1).ilog2() as usize + 1 {
+            let p = &dat[k-1];
+            let next = (0..=n-(1<<k)).map(|i| Op::prod_value(&p[i], &p[i+(1<<k-1)])).collect();
+            dat.push(next);
+        }
+        Self { dat }
+    }
+}
+
+
+
+/// Disjoint Sparse Table
+/// 
+/// 静的な列の区間積を `O(1)` で計算する。`Op::prod_value` は結合的であればよい。`Lazy` は使わない。
+/// 
+/// - 構築: `O(N log N)`
+/// - `fold`: `O(1)`
+pub struct DisjointSparseTable<Op: SegtreeOp> {
+    /// `dat[0] = A`
+    /// 
+    /// `dat[k+1][i]`: `i` を含む長さ `2^(k+1)` のブロックの中央を `m` として、`i < m` ならば `prod(A[i..m])`、そうでなければ `prod(A[m..=i])`
+    dat: Vec<Vec<Op::Value>>,
+}
+
+impl<Op: SegtreeOp> DisjointSparseTable<Op> {
+    pub fn len(&self) -> usize { self.dat[0].len() }
+    
+    pub fn fold(&self, range: impl RangeBounds<usize>) -> Op::Value {
+        let [l, r] = to_bounds(range, self.len());
+        if l == r { return Op::id_value(); }
+        if l == r-1 { return self.dat[0][l].clone(); }
+        let k = (l ^ r-1).ilog2() as usize + 1;
+        Op::prod_value(&self.dat[k][l], &self.dat[k][r-1])
+    }
+}
+
+impl<Op: SegtreeOp> FromIterator<Op::Value> for DisjointSparseTable<Op> {
+    fn from_iter<T: IntoIterator<Item = Op::Value>>(iter: T) -> Self {
+        let a: Vec<_> = iter.into_iter().collect();
+        let n = a.len();
+        let mut dat = vec![];
+        for k in 0..n.max(2).next_power_of_two().ilog2() as usize {
+            let (h, mut v) = (1 << k, a.clone());
+            for m in (h..n).step_by(2*h) {
+                for i in (m-h..m-1).rev() { v[i] = Op::prod_value(&a[i], &v[i+1]); }
+                for i in m+1..(m+h).min(n) { v[i] = Op::prod_value(&v[i-1], &a[i]); }
+            }
+            dat.push(v);
+        }
+        dat.insert(0, a);
+        Self { dat }
+    }
+}
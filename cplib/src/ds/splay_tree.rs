@@ -1,5 +1,5 @@
-use std::{cell::Cell, /* fmt::Debug, */ mem::replace, ops::{Deref, DerefMut}, ptr::NonNull};
-// use crate::cplib::util::func::to_bounds;
+use std::{cell::Cell, fmt::Debug, marker::PhantomData, mem::replace, ops::{Deref, DerefMut, Index, RangeBounds}, ptr::NonNull};
+use crate::cplib::util::func::to_bounds;
 
 
 /// [`SplayTree`] に載せる演算用 trait
@@ -21,6 +21,22 @@ pub trait SplayOp {
 }
 
 
+
+/// Splay 木
+/// 
+/// 列を管理する。`Acc` は左から畳み込んだものと右から畳み込んだものの 2 つを持ち、区間反転に対応する。
+/// 
+/// # 搭載機能
+/// 
+/// - インデックス挿入・削除・取得 `insert, remove, get, set`
+/// - 分割・連結 `split, concat`
+/// - 区間積・遅延作用・反転 `fold, act, reverse`
+/// - `iter, range, partition_point`
+/// - `Clone`, `Debug`, `FromIterator<Op::Value>`, `IntoIterator for &SplayTree`, `Index<usize>`
+/// 
+/// # Memo
+/// 
+/// splay しかしない関数は `&self` で、そうでない破壊的関数は `&mut self` にしてある。
 pub struct SplayTree<Op: SplayOp>(Cell<Option<NodeRef<Op>>>);
 
 impl<Op: SplayOp> SplayTree<Op> {
@@ -28,15 +44,8 @@ impl<Op: SplayOp> SplayTree<Op> {
     pub fn is_empty(&self) -> bool { self.0.get().is_none() }
     pub fn len(&self) -> usize { self.0.get().map_or(0, |p| p.len()) }
     
-    fn splay(&self, p: NodeRef<Op>) -> NodeRef<Op> {
-        p.splay();
-        self.0.set(Some(p));
-        p
-    }
-    
     pub fn get(&self, idx: usize) -> Option<&Op::Value> {
-        self.nth(idx);
-        self.0.get().map(|p| unsafe { &p.0.as_ref().value })
+        self.nth(idx).map(|p| unsafe { &(*p.0.as_ptr()).value })
     }
     
     /// `idx` 番目を根にして、`f(&mut root)`
@@ -47,31 +56,19 @@ impl<Op: SplayOp> SplayTree<Op> {
         Some(res)
     }
     
-    /// `idx` 番目のノードを根にして、返す。
-    /// 
-    /// # Panics
-    /// 
-    /// if not `idx < self.len()`. 特に空木について呼ぶことができない。
-    fn nth(&self, mut idx: usize) -> Option<NodeRef<Op>> {
+    /// `idx` 番目のノードを根にして、返す。範囲外のとき `None` を返す。
+    fn nth(&self, idx: usize) -> Option<NodeRef<Op>> {
         if !(idx < self.len()) { return None; }
-        let mut p = self.0.get().unwrap();
-        loop {
-            use std::cmp::Ordering::*;
-            p.push();
-            match idx.cmp(&p.cnt[0]) {
-                Less => { p = p.child[0].unwrap(); }
-                Equal => { self.splay(p); return Some(p); }
-                Greater => { idx -= p.cnt[0]+1; p = p.child[1].unwrap(); }
-            }
-        }
-    }
-    
-    fn first(&self) -> NodeRef<Op> {
-        let mut p = self.0.get().unwrap();
-        while let Some(c) = p.child[0] { p = c; }
-        self.splay(p)
+        let p = self.0.get().unwrap().nth(idx);
+        self.0.set(Some(p));
+        Some(p)
     }
     
+    /// 値を挿入する。一番右なら `idx = self.len()` とすればよい。
+    /// 
+    /// # Panics
+    /// 
+    /// if not `idx <= self.len()`
     pub fn insert(&mut self, idx: usize, value: Op::Value) {
         assert!(idx <= self.len());
         let node = NodeRef::new(value);
@@ -83,13 +80,15 @@ impl<Op: SplayOp> SplayTree<Op> {
             if pos { cur += p.cnt[0]+1; }
             let Some(c) = p.child[pos as usize] else {
                 connect(Some(p), Some(node), pos);
-                self.splay(node);
+                node.splay();
+                self.0.set(Some(node));
                 return;
             };
             p = c;
         }
     }
     
+    /// `pred(A[i-1]) == true && pred(A[i]) == false` である `i` を返す。
     pub fn partition_point(&self, pred: impl Fn(&Op::Value) -> bool) -> usize {
         let Some(mut p) = self.0.get() else { return 0; };
         let mut res = 0;
@@ -97,302 +96,236 @@ impl<Op: SplayOp> SplayTree<Op> {
             p.push();
             let pos = pred(&p.value);
             if pos { res += p.cnt[0]+1; }
-            let Some(c) = p.child[pos as usize] else { return res; };
+            let Some(c) = p.child[pos as usize] else { break; };
             p = c;
         }
+        p.splay();
+        self.0.set(Some(p));
+        res
     }
     
-    
-    
+    /// `self[at..]` を切り離して返す。
     pub fn split(&mut self, at: usize) -> SplayTree<Op> {
         assert!(at <= self.len());
-        if at == self.len() { return SplayTree::new(); }
-        if at == 0 { return replace(self, SplayTree::new()); }
-        self.nth(at);
-        let mut r = self.0.get().unwrap();
-        let mut l = r.child[0].take().unwrap();
-        
-        l.push();
-        l.parent = None;
-        self.0.set(Some(l));
-        
-        r.recalc();
-        SplayTree(Cell::new(Some(r)))
+        let [l, r] = split_at(self.0.get(), at);
+        self.0.set(l);
+        SplayTree(Cell::new(r))
     }
     
+    /// 木を右から連結する。
     pub fn concat(&mut self, r: Self) {
-        if r.is_empty() { return; }
-        let mut r = r.first();
-        r.child[0] = self.0.get();
-        r.recalc();
-        self.0.set(Some(r));
-    }
-    
-    pub fn remove(&mut self, idx: usize) -> Op::Value {
-        assert!(idx < self.len());
-        let r = self.split(idx);
-        let mp = if let Some(mut r) = r.nth(1) {
-            let m = replace(&mut r.child[0], self.0.get()).unwrap();
-            r.recalc();
-            self.0.set(Some(r));
-            m
-        } else {
-            r.0.get().unwrap()
-        };
-        unsafe { Box::from_raw(mp.0.as_ptr()).value }
-    }
-}
-
-
-
-// impl<Op: SplayOp> Debug for SplayTree<Op> where Op::Value: Debug {
-//     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-//         let Some(r) = self.0.get() else { write!(f, "<empty>") };
-//         let mut stk = vec![];
-        
-//     }
-// }
-
-/*
-
-/// Splay 木
-/// 
-/// # Features
-/// 
-/// - functions
-///   + `new, is_empty, len`
-///   + インデックス挿入・削除・取得 `insert, remove?, get(get_mut)`
-///   + 分割・マージ `split_off, merge`
-///   + 区間集約・遅延作用・反転 `fold, act, reverse`
-///   + `iter, range, partition_point`
-/// - traits
-///   + `Clone`
-///   + `Debug where Ops::Value: Debug`
-///   + `FromIterator<Value>`
-///   + `IntoIterator for &SplayTree`
-///   + `Index<usize>`: `splay.get(i).unwrap()` と等価。
-/// 
-/// # Memo
-/// 
-/// - `splay` しかしないような関数は `&self` で、そうでない破壊的関数は `&mut self` にしてある。
-pub struct SplayTree<Ops: SplayOp> (Cell<*mut Node<Ops>>);
-
-impl<Ops: SplayOp> SplayTree<Ops> {
-    fn root(&self) -> *mut Node<Ops> { self.0.get() }
-    fn set_root(&self, ptr: *mut Node<Ops>) { self.0.set(ptr); }
-    
-    pub fn new() -> Self { Self(Cell::new(null_mut())) }
-    pub fn is_empty(&self) -> bool { self.root().is_null() }
-    pub fn len(&self) -> usize { Node::len(self.root()) }
-    
-    /// 値を挿入する。一番右なら `at = self.len` とすればよい。
-    /// 
-    /// # Panics
-    /// 
-    /// `not (at <= self.len)`
-    pub fn insert(&mut self, at: usize, value: Ops::Value) {
-        assert!(at <= self.len());
-        let [l, r] = Node::split_at(self.root(), at);
-        let node = Node::new(value);
-        self.set_root(Node::merge_3(l, node, r));
+        self.0.set(merge(self.0.get(), r.0.take()));
     }
     
     /// 値を削除して返す。
     /// 
     /// # Panics
     /// 
-    /// `not (at < self.len)`
-    pub fn remove(&mut self, at: usize) -> Ops::Value {
-        assert!(at < self.len());
-        let (l, c, r) = Node::split_at_3(self.root(), at..=at).unwrap();
-        self.set_root(Node::merge(l, r));
-        unsafe { Box::from_raw(c) }.value
-    }
-    
-    /// `tree[range]` 部分を切り離す。
-    pub fn split_off(&mut self, range: impl RangeBounds<usize>) -> SplayTree<Ops> {
-        let Some((l, c, r)) = Node::split_at_3(self.root(), range) else { return SplayTree::new() };
-        self.set_root(Node::merge(l, r));
-        Self(Cell::new(c))
-    }
-    
-    /// `tree[range]` を逆順にする。
-    pub fn reverse(&mut self, range: impl RangeBounds<usize>) {
-        let Some((l, c, r)) = Node::split_at_3(self.root(), range) else { return; };
-        c.rev ^= true; c.push();
-        self.set_root(Node::merge_3(l, c, r));
-    }
-    
-    /// `tree[range]` を畳み込んだ値を返す。区間が空のとき `None` を返す。
-    pub fn fold(&self, range: impl RangeBounds<usize>) -> Option<[Ops::Acc; 2]> {
-        let Some((l, c, r)) = Node::split_at_3(self.root(), range) else { return None; };
-        c.update();
-        let res = c.acc.clone();
-        self.set_root(Node::merge_3(l, c, r));
+    /// if not `idx < self.len()`
+    pub fn remove(&mut self, idx: usize) -> Op::Value {
+        assert!(idx < self.len());
+        let [l, r] = split_at(self.0.get(), idx);
+        let [m, r] = split_at(r, 1);
+        self.0.set(merge(l, r));
+        unsafe { Box::from_raw(m.unwrap().0.as_ptr()).value }
+    }
+    
+    /// `self[range]` を切り出して `f` を呼び、元に戻す。区間が空のとき `None` を返す。
+    fn with_range<T>(&self, range: impl RangeBounds<usize>, f: impl FnOnce(NodeRef<Op>) -> T) -> Option<T> {
+        let [l, r] = to_bounds(range, self.len());
+        if l == r { return None; }
+        let [c, rr] = split_at(self.0.get(), r);
+        let [ll, c] = split_at(c, l);
+        let res = f(c.unwrap());
+        self.0.set(merge(merge(ll, c), rr));
         Some(res)
     }
     
-    /// `tree[range]` に `lazy` を作用させる。
-    pub fn act(&mut self, range: impl RangeBounds<usize>, lazy: Ops::Lazy) {
-        let Some((l, c, r)) = Node::split_at_3(self.root(), range) else { return; };
-        c.lazy = Some(lazy); c.push();
-        self.set_root(Node::merge_3(l, c, r));
+    /// `self[range]` を左から畳み込んだ値と右から畳み込んだ値を返す。区間が空のとき `None` を返す。
+    pub fn fold(&self, range: impl RangeBounds<usize>) -> Option<[Op::Acc; 2]> {
+        self.with_range(range, |c| c.acc.clone())
     }
     
-    /// 指定した位置の参照を返す。`&self[idx]` は `self.get().unwrap()` と同等。
-    /// 
-    /// 範囲外のとき `None` を返す。
-    pub fn get(&self, i: usize) -> Option<&Ops::Value> {
-        if self.len() <= i { return None; }
-        let root = Node::splay_index(Node::unwrap(self.root()), i);
-        self.set_root(root);
-        Some(&root.value)
+    /// `self[range]` に `lazy` を作用させる。
+    pub fn act(&mut self, range: impl RangeBounds<usize>, lazy: Op::Lazy) {
+        self.with_range(range, |c| c.apply(&lazy));
     }
     
-    /// 指定した位置の可変参照を返す。
-    /// 
-    /// 範囲外のとき `None` を返す。
-    pub fn get_mut(&mut self, i: usize) -> Option<RefMut<'_, Ops>> {
-        if self.len() <= i { return None; }
-        let root = Node::splay_index(Node::unwrap(self.root()), i);
-        self.set_root(root);
-        Some(RefMut(root))
-    }
-    
-    /// 木を右からマージする。
-    pub fn merge(&mut self, right: Self) {
-        self.set_root(Node::merge(self.root(), right.root()));
+    /// `self[range]` を逆順にする。
+    pub fn reverse(&mut self, range: impl RangeBounds<usize>) {
+        self.with_range(range, |c| c.flip());
     }
     
     /// `self.into_iter() == self.iter()`
-    pub fn iter(&self) -> Iter<'_, Ops> {
-        Iter { splay: self, st: 0, ed: self.len() }
-    }
-    
-    /// `range` 範囲の `Iterator` を返す。
-    pub fn range(&self, range: impl RangeBounds<usize>) -> Iter<'_, Ops> {
-        let [st, ed] = to_bounds(range, self.len());
-        Iter { splay: self, st, ed }
-    }
-    
-    /// `f(i-1) = true, f(i) = false` となる `i` を一つ返す。
-    pub fn partition_point(&self, f: impl FnMut(&Ops::Value, &[Ops::Acc; 2]) -> bool) -> usize {
-        let (i, ptr) = Node::partition_point(self.root(), f);
-        self.set_root(ptr);
-        i
+    pub fn iter(&self) -> Iter<'_, Op> { self.range(..) }
+    
+    /// `self[range]` を順に返す [`Iterator`] を返す。
+    pub fn range(&self, range: impl RangeBounds<usize>) -> Iter<'_, Op> {
+        let [l, r] = to_bounds(range, self.len());
+        let (mut front, mut back) = (vec![], vec![]);
+        if l != r {
+            let root = self.0.get().unwrap();
+            let (mut p, mut i) = (root, l);
+            loop {
+                p.push();
+                if i < p.cnt[0] { front.push(p); p = p.child[0].unwrap(); }
+                else if i == p.cnt[0] { front.push(p); break; }
+                else { i -= p.cnt[0]+1; p = p.child[1].unwrap(); }
+            }
+            let (mut p, mut i) = (root, r-1);
+            loop {
+                p.push();
+                if i > p.cnt[0] { back.push(p); i -= p.cnt[0]+1; p = p.child[1].unwrap(); }
+                else if i == p.cnt[0] { back.push(p); break; }
+                else { p = p.child[0].unwrap(); }
+            }
+        }
+        Iter { front, back, rem: r-l, _marker: PhantomData }
     }
 }
 
-impl<Ops: SplayOp> FromIterator<Ops::Value> for SplayTree<Ops> {
-    fn from_iter<T: IntoIterator<Item = Ops::Value>>(iter: T) -> Self {
-        let mut iter = iter.into_iter();
-        let mut root = if let Some(v) = iter.next() { Node::new(v) } else { return Self::new(); };
+impl<Op: SplayOp> FromIterator<Op::Value> for SplayTree<Op> {
+    fn from_iter<T: IntoIterator<Item = Op::Value>>(iter: T) -> Self {
+        let mut root = None;
         for v in iter {
-            let node = Node::new(v);
-            root.parent = node;
-            node.child[0] = root;
-            node.update();
-            root = node;
+            let node = NodeRef::new(v);
+            connect(Some(node), root, false);
+            node.recalc();
+            root = Some(node);
         }
         Self(Cell::new(root))
     }
 }
 
-impl<'a, Ops: SplayOp> IntoIterator for &'a SplayTree<Ops> {
-    type Item = &'a Ops::Value;
-    type IntoIter = Iter<'a, Ops>;
+impl<'a, Op: SplayOp> IntoIterator for &'a SplayTree<Op> {
+    type Item = &'a Op::Value;
+    type IntoIter = Iter<'a, Op>;
     fn into_iter(self) -> Self::IntoIter { self.iter() }
 }
 
-impl<Ops: SplayOp> Clone for SplayTree<Ops> {
-    fn clone(&self) -> Self { self.iter().map(|v| v.clone()).collect() }
+impl<Op: SplayOp> Clone for SplayTree<Op> {
+    fn clone(&self) -> Self { self.iter().cloned().collect() }
 }
 
-impl<Ops: SplayOp> Index<usize> for SplayTree<Ops> {
-    type Output = Ops::Value;
+impl<Op: SplayOp> Index<usize> for SplayTree<Op> {
+    type Output = Op::Value;
     fn index(&self, index: usize) -> &Self::Output { self.get(index).expect("Out of index (@ SplayTree, Index<usize>)") }
 }
 
-impl<Ops: SplayOp> Debug for SplayTree<Ops> where Ops::Value: Debug {
+impl<Op: SplayOp> Debug for SplayTree<Op> where Op::Value: Debug {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_list().entries(self.iter()).finish()
     }
 }
 
-impl<Ops: SplayOp> Drop for SplayTree<Ops> {
-    fn drop(&mut self) {}
+impl<Op: SplayOp> Drop for SplayTree<Op> {
+    fn drop(&mut self) {
+        let mut stk: Vec<_> = self.0.take().into_iter().collect();
+        while let Some(p) = stk.pop() {
+            stk.extend(p.child.into_iter().flatten());
+            unsafe { drop(Box::from_raw(p.0.as_ptr())); }
+        }
+    }
 }
 
 
 
-/// [`SplayTree::entry`] の返り値型。
+/// [`SplayTree::iter`], [`SplayTree::range`] の返り値型。
 /// 
-/// # Constraints
-/// 
-/// `self.0` が `entry` 対象で、かつ根であること。
-pub struct RefMut<'a, Ops: SplayOp>(&'a mut Node<Ops>);
-
-impl<Ops: SplayOp> Deref for RefMut<'_, Ops> {
-    type Target = Ops::Value;
-    fn deref(&self) -> &Self::Target { &self.0.value }
-}
-
-impl<Ops: SplayOp> DerefMut for RefMut<'_, Ops> {
-    fn deref_mut(&mut self) -> &mut Self::Target { &mut self.0.value }
-}
-
-impl<Ops: SplayOp> Drop for RefMut<'_, Ops> {
-    fn drop(&mut self) { self.0.update(); }
-}
-
-
-
-/// [`SplayTree::iter`], [`SplayTree::range`] の返り値型。保持する区間は `st..ed` で表される。
+/// `front` の末尾が次に返すノードであり、それ以外は左の子に降りた祖先である。`back` はその逆。
 /// 
 /// # Constraints
 /// 
-/// [`Iter`] 存在中に破壊的変更をしない。
-pub struct Iter<'a, Ops: SplayOp> {
-    splay: &'a SplayTree<Ops>,
-    st: usize,
-    ed: usize
+/// [`Iter`] 存在中に splay 木を操作しない。
+pub struct Iter<'a, Op: SplayOp> {
+    front: Vec<NodeRef<Op>>,
+    back: Vec<NodeRef<Op>>,
+    rem: usize,
+    _marker: PhantomData<&'a SplayTree<Op>>,
 }
 
-impl<'a, Ops: SplayOp> Iterator for Iter<'a, Ops> {
-    type Item = &'a Ops::Value;
+impl<'a, Op: SplayOp> Iterator for Iter<'a, Op> {
+    type Item = &'a Op::Value;
     
     fn next(&mut self) -> Option<Self::Item> {
-        if self.st == self.ed { return None; }
-        self.st += 1;
-        Some(&self.splay[self.st-1])
+        if self.rem == 0 { return None; }
+        self.rem -= 1;
+        let p = self.front.pop().unwrap();
+        let mut c = p.child[1];
+        while let Some(q) = c { q.push(); self.front.push(q); c = q.child[0]; }
+        Some(unsafe { &(*p.0.as_ptr()).value })
     }
+    
+    fn size_hint(&self) -> (usize, Option<usize>) { (self.rem, Some(self.rem)) }
 }
 
-impl<'a, Ops: SplayOp> DoubleEndedIterator for Iter<'a, Ops> {
+impl<'a, Op: SplayOp> DoubleEndedIterator for Iter<'a, Op> {
     fn next_back(&mut self) -> Option<Self::Item> {
-        if self.st == self.ed { return None; }
-        self.ed -= 1;
-        Some(&self.splay[self.ed])
+        if self.rem == 0 { return None; }
+        self.rem -= 1;
+        let p = self.back.pop().unwrap();
+        let mut c = p.child[0];
+        while let Some(q) = c { q.push(); self.back.push(q); c = q.child[1]; }
+        Some(unsafe { &(*p.0.as_ptr()).value })
     }
 }
-*/
+
+impl<'a, Op: SplayOp> ExactSizeIterator for Iter<'a, Op> {}
 
 
 
+/// `root[..at]`, `root[at..]` を返す。
+fn split_at<Op: SplayOp>(root: Option<NodeRef<Op>>, at: usize) -> [Option<NodeRef<Op>>; 2] {
+    let Some(root) = root else { return [None, None]; };
+    if at == 0 { return [None, Some(root)]; }
+    if at == root.len() { return [Some(root), None]; }
+    let mut r = root.nth(at);
+    let l = r.child[0].take();
+    if let Some(mut l) = l { l.parent = None; }
+    r.recalc();
+    [l, Some(r)]
+}
+
+/// `l`, `r` を連結した木の根を返す。
+pub(crate) fn merge<Op: SplayOp>(l: Option<NodeRef<Op>>, r: Option<NodeRef<Op>>) -> Option<NodeRef<Op>> {
+    let Some(l) = l else { return r; };
+    let Some(_) = r else { return Some(l); };
+    let l = l.nth(l.len()-1);
+    connect(Some(l), r, true);
+    l.recalc();
+    Some(l)
+}
+
 
 
 /// 遅延 `lazy, rev` の対象は自身を含まない。
-struct Node<Op: SplayOp> {
-    parent: Option<NodeRef<Op>>,
-    child: [Option<NodeRef<Op>>; 2],
-    value: Op::Value,
-    acc: [Op::Acc; 2],
+/// 
+/// `parent` が自身を子に持たないとき、`parent` は別の splay 木への path-parent である (link-cut tree 用)。
+pub(crate) struct Node<Op: SplayOp> {
+    pub(crate) parent: Option<NodeRef<Op>>,
+    pub(crate) child: [Option<NodeRef<Op>>; 2],
+    pub(crate) value: Op::Value,
+    pub(crate) acc: [Op::Acc; 2],
     lazy: Option<Op::Lazy>,
     rev: bool,
-    cnt: [usize; 2]
+    pub(crate) cnt: [usize; 2]
 }
 
-struct NodeRef<Op: SplayOp>(NonNull<Node<Op>>);
+impl<Op: SplayOp> Node<Op> {
+    pub(crate) fn new(value: Op::Value) -> Self {
+        Node {
+            parent: None,
+            child: [None, None],
+            acc: [Op::to_acc(&value), Op::to_acc(&value)],
+            value,
+            lazy: None,
+            rev: false,
+            cnt: [0, 0]
+        }
+    }
+}
+
+pub(crate) struct NodeRef<Op: SplayOp>(pub(crate) NonNull<Node<Op>>);
 
 fn connect<Op: SplayOp>(p: Option<NodeRef<Op>>, c: Option<NodeRef<Op>>, pos: bool) {
     if let Some(mut p) = p { p.child[pos as usize] = c; }
@@ -401,25 +334,24 @@ fn connect<Op: SplayOp>(p: Option<NodeRef<Op>>, c: Option<NodeRef<Op>>, pos: boo
 
 impl<Op: SplayOp> NodeRef<Op> {
     fn new(value: Op::Value) -> Self {
-        let node = Node {
-            parent: None,
-            child: [None, None],
-            acc: [Op::to_acc(&value), Op::to_acc(&value)],
-            value,
-            lazy: None,
-            rev: false,
-            cnt: [0, 0]
-        };
         unsafe {
-            Self(NonNull::new_unchecked(Box::into_raw(Box::new(node))))
+            Self(NonNull::new_unchecked(Box::into_raw(Box::new(Node::new(value)))))
         }
     }
     
-    fn len(self) -> usize { 1 + self.cnt[0] + self.cnt[1] }
+    pub(crate) fn len(self) -> usize { 1 + self.cnt[0] + self.cnt[1] }
+    
+    /// 自身が splay 木の根であるか判定する。
+    pub(crate) fn is_root(self) -> bool {
+        self.parent.map_or(true, |p| p.child[0] != Some(self) && p.child[1] != Some(self))
+    }
     
     /// parent の位置に self が来るよう回転する。
-    fn rotate(self, p: Self, pos: bool) {
-        connect(p.parent, Some(self), p.parent.map_or(false, |g| g.child[1] == Some(p)));
+    fn rotate(mut self, p: Self, pos: bool) {
+        if let Some(mut g) = p.parent {
+            if let Some(c) = g.child.iter_mut().find(|c| **c == Some(p)) { *c = Some(self); }
+        }
+        self.parent = p.parent;
         connect(Some(p), self.child[!pos as usize], pos);
         connect(Some(self), Some(p), !pos);
         p.recalc();
@@ -427,7 +359,7 @@ impl<Op: SplayOp> NodeRef<Op> {
     }
     
     /// `cnt`, `acc` を再計算する。
-    fn recalc(mut self) {
+    pub(crate) fn recalc(mut self) {
         self.cnt = [0; 2];
         self.acc[0] = Op::to_acc(&self.value);
         self.acc[1] = self.acc[0].clone();
@@ -440,10 +372,12 @@ impl<Op: SplayOp> NodeRef<Op> {
     }
     
     /// `self` を splay する。根から `self` が全て `push` されている必要がある。
-    fn splay(self) {
-        while let Some(p) = self.parent {
+    pub(crate) fn splay(self) {
+        while !self.is_root() {
+            let p = self.parent.unwrap();
             let pos = p.child[1] == Some(self);
-            let Some(pp) = p.parent else { self.rotate(p, pos); return; };
+            if p.is_root() { self.rotate(p, pos); return; }
+            let pp = p.parent.unwrap();
             if pos == (pp.child[1] == Some(p)) {
                 // zig-zig
                 p.rotate(pp, pos); self.rotate(p, pos);
@@ -454,43 +388,61 @@ impl<Op: SplayOp> NodeRef<Op> {
         }
     }
     
-    /// `lazy`, `rev` を解決・伝搬する。特に、子の `value, acc` は遅延が適用された状態になる。
-    fn push(mut self) {
-        if let Some(lazy) = self.lazy.take() {
-            for c in self.child {
-                let Some(mut c) = c else { continue; };
-                Op::act_value(&mut c.value, &lazy);
-                Op::act_acc(&mut c.acc[0], &lazy);
-                Op::act_acc(&mut c.acc[1], &lazy);
-                c.comp_lazy(&lazy);
-            }
-        }
-        
-        if replace(&mut self.rev, false) {
-            for c in self.child {
-                let Some(mut c) = c else { continue; };
-                c.cnt.swap(0, 1);
-                c.child.swap(0, 1);
-                c.acc.swap(0, 1);
-                c.rev ^= true;
+    /// 根 `self` の部分木で `idx` 番目のノードを splay して返す。
+    fn nth(self, mut idx: usize) -> Self {
+        let mut p = self;
+        loop {
+            use std::cmp::Ordering::*;
+            p.push();
+            match idx.cmp(&p.cnt[0]) {
+                Less => { p = p.child[0].unwrap(); }
+                Equal => { p.splay(); return p; }
+                Greater => { idx -= p.cnt[0]+1; p = p.child[1].unwrap(); }
             }
         }
     }
     
-    fn comp_lazy(mut self, lazy: &Op::Lazy) {
+    /// 根から `self` までを `push` する。
+    pub(crate) fn push_path(self) {
+        let mut path = vec![self];
+        let mut p = self;
+        while !p.is_root() { p = p.parent.unwrap(); path.push(p); }
+        for p in path.into_iter().rev() { p.push(); }
+    }
+    
+    /// 部分木全体に `lazy` を作用させる。
+    pub(crate) fn apply(mut self, lazy: &Op::Lazy) {
+        Op::act_value(&mut self.value, lazy);
+        Op::act_acc(&mut self.acc[0], lazy);
+        Op::act_acc(&mut self.acc[1], lazy);
         if let Some(l) = self.lazy.as_mut() {
             Op::comp_lazy(l, lazy);
         } else {
             self.lazy = Some(lazy.clone());
         }
     }
+    
+    /// 部分木全体を反転させる。
+    pub(crate) fn flip(mut self) {
+        self.cnt.swap(0, 1);
+        self.child.swap(0, 1);
+        self.acc.swap(0, 1);
+        self.rev ^= true;
+    }
+    
+    /// `lazy`, `rev` を解決・伝搬する。特に、子の `value, acc` は遅延が適用された状態になる。
+    pub(crate) fn push(mut self) {
+        if let Some(lazy) = self.lazy.take() {
+            for c in self.child.into_iter().flatten() { c.apply(&lazy); }
+        }
+        if replace(&mut self.rev, false) {
+            for c in self.child.into_iter().flatten() { c.flip(); }
+        }
+    }
 }
 
 
 
-
-
-
 impl<Op: SplayOp> Deref for NodeRef<Op> {
     type Target = Node<Op>;
     fn deref(&self) -> &Self::Target { unsafe { self.0.as_ref() } }
@@ -506,211 +458,3 @@ impl<Op: SplayOp> PartialEq for NodeRef<Op> {
     fn eq(&self, other: &Self) -> bool { self.0 == other.0 }
 }
 impl<Op: SplayOp> Eq for NodeRef<Op> {}
-
-
-
-/*
-/// Splay 木の `Node`
-struct Node<Ops: SplayOps> {
-    pub parent: *mut Self,
-    pub child: [*mut Self; 2],
-    pub len: usize,
-    pub rev: bool,
-    pub value: Ops::Value,
-    pub acc: [Ops::Acc; 2],
-    pub lazy: Option<Ops::Lazy>
-}
-
-impl<Ops: SplayOps> Node<Ops> {
-    /// leak した `Node` を返す。
-    fn new<'a>(value: Ops::Value) -> &'a mut Self {
-        let acc = Ops::to_acc(&value);
-        let tmp = Self { parent: null_mut(), child: [null_mut(); 2], len: 1, rev: false, value, acc: [acc.clone(), acc], lazy: None };
-        Box::leak(Box::new(tmp))
-    }
-    
-    fn get<'a>(ptr: *const Self) -> Option<&'a Self> { unsafe { ptr.as_ref() } }
-    fn get_mut<'a>(ptr: *mut Self) -> Option<&'a mut Self> { unsafe { ptr.as_mut() } }
-    fn unwrap<'a>(ptr: *mut Self) -> &'a mut Self { unsafe { &mut *ptr } }
-    
-    #[allow(dead_code)]
-    fn debug(&self) -> String where Ops::Value: Debug, Ops::Acc: Debug, Ops::Lazy: Debug {
-        let mut res = String::new();
-        if let Some(l) = Node::get(self.child[0]) { res += &l.debug(); res += "\n"; }
-        res += &format!("{:?}: par = {:?}, child = {:?}, len = {}, rev = {}, value = {:?}, acc = {:?}, lazy = {:?}", self as *const _, self.parent, self.child, self.len, self.rev, self.value, self.acc, self.lazy);
-        if let Some(r) = Node::get(self.child[1]) { res += "\n"; res += &r.debug(); }
-        res
-    }
-    
-    /// 部分木の大きさを返す。
-    fn len(ptr: *mut Self) -> usize { Node::get(ptr).map_or(0, |ptr| ptr.len) }
-    
-    /// 親 `p` に対する `self` の位置を返す。
-    fn pos(&self, p: &Self) -> usize { ptr_eq(p.child[1], self) as usize }
-    
-    /// `self` が根になるよう、適切に回転させる。
-    fn splay(&mut self) {
-        while let Some(p) = Node::get_mut(self.parent) {
-            let pos = self.pos(p);
-            let Some(g) = Node::get_mut(p.parent) else { self.rotate(p, pos); return; };
-            if pos == p.pos(g) {
-                p.rotate(g, pos); self.rotate(p, pos);
-            } else {
-                self.rotate(p, pos); self.rotate(g, pos^1);
-            }
-        }
-    }
-    
-    /// `self` が自分自身の親の位置に来るように回転させる。
-    /// 
-    /// `p.child[pos] = self` である必要がある。
-    /// 
-    /// # Memo
-    /// 
-    /// - `g --[pos]--> self`
-    /// - `p --[pos]--> self.child[pos^1]`
-    /// - `self --[pos^1]--> p`
-    fn rotate(&mut self, p: &mut Self, pos: usize) {
-        self.push();
-        self.parent = p.parent;
-        if let Some(g) = Node::get_mut(self.parent) { g.child[p.pos(g)] = self; }
-        p.child[pos] = self.child[pos^1];
-        if let Some(c) = Node::get_mut(p.child[pos]) { c.parent = p; }
-        p.parent = self;
-        self.child[pos^1] = p;
-        p.update(); self.update();
-    }
-    
-    /// 遅延作用と反転を行い、子に伝搬させる。
-    fn push(&mut self) {
-        if let Some(ad) = self.lazy.take() {
-            Ops::act_value(&mut self.value, &ad);
-            Ops::act_acc(&mut self.acc[0], &ad);
-            Ops::act_acc(&mut self.acc[1], &ad);
-            
-            for c in self.child {
-                let Some(Node { lazy, .. }) = Node::get_mut(c) else { continue; };
-                if let Some(lazy) = lazy { Ops::comp_lazy(lazy, &ad); } else { *lazy = Some(ad.clone()); }
-            }
-        }
-        
-        if replace(&mut self.rev, false) {
-            self.child.swap(0, 1);
-            self.acc.swap(0, 1);
-            for c  in self.child { if let Some(c) = Node::get_mut(c) { c.rev ^= true; } }
-        }
-    }
-    
-    /// `self.child` に対して [`Node::push`] してから `self.len, self.acc` を子の情報から更新する。
-    fn update(&mut self) {
-        self.len = 1;
-        self.acc = { let tmp = Ops::to_acc(&self.value); [tmp.clone(), tmp] };
-        for pos in [0, 1] {
-            let Some(c) = Node::get_mut(self.child[pos]) else { continue; };
-            c.push();
-            self.len += c.len;
-            self.acc[pos] = Ops::prod_acc(&c.acc[pos], &self.acc[pos]);
-            self.acc[pos^1] = Ops::prod_acc(&self.acc[pos^1], &c.acc[pos^1]);
-        }
-    }
-    
-    /// 頂点 `i` を `splay` して返す。
-    /// 
-    /// # Constraints
-    /// 
-    /// `i < root.len`
-    fn splay_index(mut root: &mut Self, mut i: usize) -> &mut Self {
-        loop {
-            // root.push();
-            for c in root.child { if let Some(c) = Node::get_mut(c) { c.push(); } }
-            let ls = Node::len(root.child[0]);
-            
-            use std::cmp::Ordering::*;
-            root = match i.cmp(&ls) {
-                Less => { Node::unwrap(root.child[0]) }
-                Equal => { root.splay(); return root; }
-                Greater => { i -= ls + 1; Node::unwrap(root.child[1]) }
-            }
-        }
-    }
-    
-    /// マージした木を返す。
-    /// 
-    /// `l` の一番右の頂点が根になるよう `splay` した後に `r` と繋げる。
-    fn merge(l: *mut Self, r: *mut Self) -> *mut Self {
-        let Some(mut left) = Node::get_mut(l) else { return r; };
-        let Some(right) = Node::get_mut(r) else { return left; };
-        left = Self::splay_index(left, left.len-1);
-        left.push();
-        left.child[1] = right; right.parent = left;
-        left.update();
-        left
-    }
-    
-    /// `tree[..at], tree[at..]` で構成される 2 つの木を返す。
-    /// 
-    /// # Constraints
-    /// 
-    /// `at <= root.len`
-    /// 
-    /// # Memo
-    /// 
-    /// `splay_index(at)` してから切る。
-    fn split_at(root: *mut Self, at: usize) -> [*mut Self; 2] {
-        let Some(mut root) = Node::get_mut(root) else { return [null_mut(), null_mut()]; };
-        if at == 0 { return [null_mut(), root]; }
-        if at == root.len { return [root, null_mut()]; }
-        root = Self::splay_index(root, at);
-        root.push();
-        let left = Node::unwrap(replace(&mut root.child[0], null_mut()));
-        left.parent = null_mut();
-        root.update();
-        [left, root]
-    }
-    
-    /// `tree[..st], tree[st..ed], tree[ed..]` を返す。
-    /// 
-    /// `range` が空区間であるとき `None` を返す。
-    /// 
-    /// # Panic
-    /// 
-    /// `range` が `0..self.len` の範囲外であるとき。
-    fn split_at_3<'a>(root: *mut Self, range: impl RangeBounds<usize>) -> Option<(*mut Self, &'a mut Self, *mut Self)> {
-        let [st, ed] = to_bounds(range, Node::len(root));
-        if st == ed { return None; }
-        let [c, r] = Self::split_at(root, ed);
-        let [l, c] = Self::split_at(c, st);
-        Some((l, Node::unwrap(c), r))
-    }
-    
-    fn merge_3(l: *mut Self, c: *mut Self, r: *mut Self) -> *mut Self {
-        Self::merge(Self::merge(l, c), r)
-    }
-    
-    /// `f(i-1) = true, f(i) = false` となる `i` を一つ探す。
-    /// 
-    /// `j = min(i, len-1)` として、`splay(j)` したあと `(i, Node[j] (root))` を返す。
-    /// 
-    /// ただし `f(-1) = true, f(len) = false` とする。
-    fn partition_point(root: *mut Self, mut f: impl FnMut(&Ops::Value, &[Ops::Acc; 2]) -> bool) -> (usize, *mut Self) {
-        let Some(mut now) = Node::get_mut(root) else { return (0, root); };
-        let mut okng = [(0, null_mut()), (now.len, null_mut())];
-        loop {
-            let pos = !f(&now.value, &now.acc) as usize;
-            if pos == 0 {
-                okng[pos].0 += Node::len(now.child[pos]);
-            } else {
-                okng[pos].0 -= Node::len(now.child[pos]) + 1;
-            }
-            okng[pos].1 = now;
-            let Some(c) = Node::get_mut(now.child[pos^1]) else { break; };
-            c.update(); now = c;
-        }
-        
-        let ptr = Node::get_mut(okng[1].1).unwrap_or_else(|| Node::unwrap(okng[0].1));
-        ptr.splay();
-        (okng[1].0, ptr)
-    }
-}
-
-*/
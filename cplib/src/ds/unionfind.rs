@@ -1,27 +1,86 @@
 use std::fmt::Debug;
 
-pub trait Abelian {
+/// 群。`add` は可換でなくてよい。
+/// 
+/// 加法的に書いているが、`sub(l, r) = add(l, inv(r))` である。
+pub trait Group {
     type T: Clone + Eq;
     fn e() -> Self::T;
     fn add(l: &Self::T, r: &Self::T) -> Self::T;
     fn inv(x: &Self::T) -> Self::T;
 
     fn sub(l: &Self::T, r: &Self::T) -> Self::T { Self::add(l, &Self::inv(r)) }
+    
+    /// `x` の `n` 倍を返す。
+    fn mul(x: &Self::T, mut n: usize) -> Self::T {
+        let (mut res, mut x) = (Self::e(), x.clone());
+        while n != 0 {
+            if n&1 == 1 { res = Self::add(&res, &x); }
+            x = Self::add(&x, &x);
+            n >>= 1;
+        }
+        res
+    }
+}
+
+/// 可換群
+pub trait Abelian: Group {}
+
+/// Potential を持たない
+impl Group for () {
+    type T = ();
+    fn e() {}
+    fn add(_: &(), _: &()) {}
+    fn inv(_: &()) {}
 }
+impl Abelian for () {}
 
 pub struct Xor;
-impl Abelian for Xor {
+impl Group for Xor {
     type T = bool;
     fn e() -> bool { false }
     fn add(l: &bool, r: &bool) -> bool { l^r }
     fn inv(x: &bool) -> bool { *x }
 }
+impl Abelian for Xor {}
+
+pub struct XorU64;
+impl Group for XorU64 {
+    type T = u64;
+    fn e() -> u64 { 0 }
+    fn add(l: &u64, r: &u64) -> u64 { l^r }
+    fn inv(x: &u64) -> u64 { *x }
+}
+impl Abelian for XorU64 {}
+
+pub struct AddI64;
+impl Group for AddI64 {
+    type T = i64;
+    fn e() -> i64 { 0 }
+    fn add(l: &i64, r: &i64) -> i64 { l+r }
+    fn inv(x: &i64) -> i64 { -x }
+    fn mul(x: &i64, n: usize) -> i64 { x * n as i64 }
+}
+impl Abelian for AddI64 {}
+
+/// `0..N` の置換群。`add(l, r)` は `l` の後に `r` を適用する置換 `x -> r[l[x]]` である。
+pub struct Perm<const N: usize>;
+impl<const N: usize> Group for Perm<N> {
+    type T = [usize; N];
+    fn e() -> [usize; N] { std::array::from_fn(|i| i) }
+    fn add(l: &[usize; N], r: &[usize; N]) -> [usize; N] { std::array::from_fn(|i| r[l[i]]) }
+    fn inv(x: &[usize; N]) -> [usize; N] {
+        let mut res = [0; N];
+        for i in 0..N { res[x[i]] = i; }
+        res
+    }
+}
 
 
 
 /// Potentialized Union Find (union by size, path compression)
 /// 
-/// Potential として可換群が乗る。
+/// Potential として群 [`Group`] が乗る。非可換でもよく、`P[i] - P[j]` は `P[i] + (-P[j])` を表す。
 /// 
 /// # 参考
 /// 
@@ -34,7 +93,7 @@ impl Abelian for Xor {
 /// # 例題
 /// 
 /// - Potentialized Union Find: <https://atcoder.jp/contests/abc328/tasks/abc328_f>
-pub struct UnionFind<Op: Abelian> {
+pub struct UnionFind<Op: Group> {
     par: Vec<usize>,
     size: Vec<usize>,
     diff: Vec<Op::T>,
@@ -47,7 +106,7 @@ impl UnionFind<Xor> {
     pub fn new_xor(len: usize) -> Self { Self::new(len) }
 }
 
-impl<Op: Abelian> UnionFind<Op> {
+impl<Op: Group> UnionFind<Op> {
     pub fn new(len: usize) -> Self {
         UnionFind { par: (0..len).collect(), size: vec![1; len], diff: vec![Op::e(); len], next: (0..len).collect() }
     }
@@ -100,7 +159,7 @@ impl<Op: Abelian> UnionFind<Op> {
     /// 操作前について `size[old] <= size[new]` が保証される。
     pub fn merge(&mut self, i: usize, j: usize, mut w: Op::T) -> (usize, usize) {
         let (mut old, mut new) = (self.leader(i), self.leader(j));
-        w = Op::sub(&Op::add(&w, &self.diff[j]), &self.diff[i]);
+        w = Op::add(&Op::add(&Op::inv(&self.diff[i]), &w), &self.diff[j]);
         if old == new { return if w == Op::e() { (old, !0) } else { (!0, !0) } }
         if !(self.size[old] <= self.size[new]) { (old, new) = (new, old); w = Op::inv(&w); }
         self.par[old] = new;
@@ -133,21 +192,27 @@ impl<Op: Abelian> UnionFind<Op> {
     }
 }
 
-impl<Op: Abelian> Clone for UnionFind<Op> {
+impl<Op: Group> Clone for UnionFind<Op> {
     fn clone(&self) -> Self {
         Self { par: self.par.clone(), size: self.size.clone(), diff: self.diff.clone(), next: self.next.clone() }
     }
 }
 
-impl<Op: Abelian> std::fmt::Debug for UnionFind<Op> where Op::T: Debug {
+impl<Op: Group> std::fmt::Debug for UnionFind<Op> where Op::T: Debug {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let g = self.clone().groups();
-        let mut s = String::new();
-        if std::any::type_name::<Op::T>() == "()" {
-            for g in g { if !g.is_empty() { s += &format!("{g:?}, "); } }
-        } else {
-            panic!();
+        let mut uf = self.clone();
+        let unit = std::any::type_name::<Op::T>() == "()";
+        let mut s = vec![];
+        for g in uf.groups() {
+            if g.is_empty() { continue; }
+            if unit {
+                s.push(format!("{g:?}"));
+            } else {
+                let l = uf.leader(g[0]);
+                let p: Vec<_> = g.iter().map(|&i| format!("{i}: {:?}", uf.diff(i, l))).collect();
+                s.push(format!("{{{}}}", p.join(", ")));
+            }
         }
-        write!(f, "[ {} ]", &s[..s.len()-2])
+        write!(f, "[ {} ]", s.join(", "))
     }
 }
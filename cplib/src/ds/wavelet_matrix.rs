@@ -0,0 +1,215 @@
+//! Wavelet Matrix
+
+use std::ops::RangeBounds;
+use crate::cplib::util::func::to_bounds;
+
+
+
+/// rank, select を計算できる bit 列
+pub struct BitVector {
+    len: usize,
+    bit: Vec<u64>,
+    /// `cnt[i]`: `bit[..i]` の `1` の個数
+    cnt: Vec<usize>,
+}
+
+impl BitVector {
+    pub fn new(v: &[bool]) -> Self {
+        let mut bit = vec![0u64; v.len()/64 + 1];
+        for (i, &b) in v.iter().enumerate() { if b { bit[i >> 6] |= 1 << (i & 63); } }
+        let mut cnt = vec![0];
+        for w in &bit { cnt.push(cnt.last().unwrap() + w.count_ones() as usize); }
+        Self { len: v.len(), bit, cnt }
+    }
+    
+    pub fn len(&self) -> usize { self.len }
+    
+    pub fn get(&self, i: usize) -> bool { self.bit[i >> 6] >> (i & 63) & 1 == 1 }
+    
+    /// `[..i]` に含まれる `b` の個数を返す。
+    pub fn rank(&self, b: bool, i: usize) -> usize {
+        let one = self.cnt[i >> 6] + (self.bit[i >> 6] & (1 << (i & 63)) - 1).count_ones() as usize;
+        if b { one } else { i - one }
+    }
+    
+    /// `k` 番目 (0-indexed) の `b` の位置を返す。
+    pub fn select(&self, b: bool, k: usize) -> Option<usize> {
+        let cnt = |w: usize| if b { self.cnt[w] } else { 64*w - self.cnt[w] };
+        if self.rank(b, self.len) <= k { return None; }
+        let (mut ok, mut ng) = (0, self.bit.len());
+        while ng-ok > 1 {
+            let m = (ok+ng)/2;
+            if cnt(m) <= k { ok = m; } else { ng = m; }
+        }
+        let (mut w, mut k) = (if b { self.bit[ok] } else { !self.bit[ok] }, k - cnt(ok));
+        while k != 0 { w &= w-1; k -= 1; }
+        Some(64*ok + w.trailing_zeros() as usize)
+    }
+}
+
+
+
+/// Wavelet Matrix
+/// 
+/// 非負整数列 `A` に対して、区間の k 番目の値や、区間内で値が範囲に含まれる個数などを計算する。値の bit 数を `B` とする。
+/// 
+/// - 構築: `O(N B)`
+/// - 各クエリ: `O(B)` (`select` のみ `O(B log N)`)
+/// 
+/// [`WaveletMatrix::new_with_sum`] で構築すると、各段の累積和を持ち [`WaveletMatrix::sum_smallest`] が使える。
+pub struct WaveletMatrix {
+    len: usize,
+    /// `bv[d]`: 上から `d` 番目の bit
+    bv: Vec<BitVector>,
+    /// `mid[d]`: 段 `d` の `0` の個数
+    mid: Vec<usize>,
+    /// `sum[d]`: 段 `d` で安定ソートした後の列の累積和
+    sum: Vec<Vec<u64>>,
+}
+
+impl WaveletMatrix {
+    pub fn new(a: &[u64]) -> Self { Self::build(a, false) }
+    
+    /// 累積和を `u64` で持つので、`A` の総和が `u64` に収まる必要がある。
+    pub fn new_with_sum(a: &[u64]) -> Self { Self::build(a, true) }
+    
+    fn build(a: &[u64], with_sum: bool) -> Self {
+        let log = (64 - a.iter().max().unwrap_or(&0).leading_zeros() as usize).max(1);
+        let (mut bv, mut mid, mut sum) = (vec![], vec![], vec![]);
+        let mut cur = a.to_vec();
+        for d in (0..log).rev() {
+            let bits: Vec<bool> = cur.iter().map(|&x| x >> d & 1 == 1).collect();
+            bv.push(BitVector::new(&bits));
+            let (mut zero, one): (Vec<u64>, Vec<u64>) = cur.iter().partition(|&&x| x >> d & 1 == 0);
+            mid.push(zero.len());
+            zero.extend(one);
+            cur = zero;
+            if with_sum {
+                let mut s = vec![0];
+                for &x in &cur { s.push(s.last().unwrap() + x); }
+                sum.push(s);
+            }
+        }
+        Self { len: a.len(), bv, mid, sum }
+    }
+    
+    pub fn len(&self) -> usize { self.len }
+    
+    fn log(&self) -> usize { self.bv.len() }
+    
+    pub fn get(&self, mut i: usize) -> u64 {
+        assert!(i < self.len);
+        let mut res = 0;
+        for d in 0..self.log() {
+            let b = self.bv[d].get(i);
+            res = res << 1 | b as u64;
+            i = self.down(d, b, i);
+        }
+        res
+    }
+    
+    /// 段 `d` の位置 `i` が次の段で移る位置を返す。
+    fn down(&self, d: usize, b: bool, i: usize) -> usize {
+        if b { self.mid[d] + self.bv[d].rank(true, i) } else { self.bv[d].rank(false, i) }
+    }
+    
+    /// `A[..r]` に含まれる `x` の個数を返す。
+    pub fn rank(&self, x: u64, r: usize) -> usize {
+        if x.checked_shr(self.log() as u32).unwrap_or(0) != 0 { return 0; }
+        let (mut l, mut r) = (0, r);
+        for d in 0..self.log() {
+            let b = x >> (self.log()-1-d) & 1 == 1;
+            l = self.down(d, b, l); r = self.down(d, b, r);
+        }
+        r - l
+    }
+    
+    /// `k` 番目 (0-indexed) の `x` の位置を返す。
+    pub fn select(&self, x: u64, k: usize) -> Option<usize> {
+        if self.rank(x, self.len) <= k { return None; }
+        let mut p = 0;
+        for d in 0..self.log() { p = self.down(d, x >> (self.log()-1-d) & 1 == 1, p); }
+        p += k;
+        for d in (0..self.log()).rev() {
+            let b = x >> (self.log()-1-d) & 1 == 1;
+            p = if b { self.bv[d].select(true, p - self.mid[d]) } else { self.bv[d].select(false, p) }.unwrap();
+        }
+        Some(p)
+    }
+    
+    /// `A[range]` で `k` 番目 (0-indexed) に小さい値を返す。
+    pub fn kth_smallest(&self, range: impl RangeBounds<usize>, mut k: usize) -> Option<u64> {
+        let [mut l, mut r] = to_bounds(range, self.len);
+        if r-l <= k { return None; }
+        let mut res = 0;
+        for d in 0..self.log() {
+            let z = self.bv[d].rank(false, r) - self.bv[d].rank(false, l);
+            let b = z <= k;
+            if b { k -= z; }
+            res = res << 1 | b as u64;
+            l = self.down(d, b, l); r = self.down(d, b, r);
+        }
+        Some(res)
+    }
+    
+    /// `A[range]` で `k` 番目 (0-indexed) に大きい値を返す。
+    pub fn kth_largest(&self, range: impl RangeBounds<usize>, k: usize) -> Option<u64> {
+        let [l, r] = to_bounds(range, self.len);
+        if r-l <= k { return None; }
+        self.kth_smallest(l..r, r-l-1-k)
+    }
+    
+    /// `A[range]` の小さい方から `k` 個の和を返す。`k` が区間長より大きいときは区間全体の和を返す。
+    /// 
+    /// # Panics
+    /// 
+    /// [`WaveletMatrix::new_with_sum`] で構築していないとき。
+    pub fn sum_smallest(&self, range: impl RangeBounds<usize>, mut k: usize) -> u64 {
+        assert!(!self.sum.is_empty() || self.len == 0);
+        let [mut l, mut r] = to_bounds(range, self.len);
+        k = k.min(r-l);
+        let (mut res, mut x) = (0, 0);
+        for d in 0..self.log() {
+            let z = self.bv[d].rank(false, r) - self.bv[d].rank(false, l);
+            let (l0, r0) = (self.down(d, false, l), self.down(d, false, r));
+            let b = z < k;
+            if b { k -= z; res += self.sum[d][r0] - self.sum[d][l0]; }
+            x = x << 1 | b as u64;
+            l = self.down(d, b, l); r = self.down(d, b, r);
+        }
+        res + x * k as u64
+    }
+    
+    /// `A[range]` に含まれる `x` 未満の値の個数を返す。
+    pub fn count_less(&self, range: impl RangeBounds<usize>, x: u64) -> usize {
+        let [mut l, mut r] = to_bounds(range, self.len);
+        if x.checked_shr(self.log() as u32).unwrap_or(0) != 0 { return r-l; }
+        let mut res = 0;
+        for d in 0..self.log() {
+            let b = x >> (self.log()-1-d) & 1 == 1;
+            if b { res += self.bv[d].rank(false, r) - self.bv[d].rank(false, l); }
+            l = self.down(d, b, l); r = self.down(d, b, r);
+        }
+        res
+    }
+    
+    /// `A[range]` に含まれる `lo..hi` の値の個数を返す。
+    pub fn range_freq(&self, range: impl RangeBounds<usize>, lo: u64, hi: u64) -> usize {
+        let [l, r] = to_bounds(range, self.len);
+        if hi <= lo { return 0; }
+        self.count_less(l..r, hi) - self.count_less(l..r, lo)
+    }
+    
+    /// `A[range]` に含まれる `upper` 未満の最大値を返す。
+    pub fn prev_value(&self, range: impl RangeBounds<usize>, upper: u64) -> Option<u64> {
+        let [l, r] = to_bounds(range, self.len);
+        let c = self.count_less(l..r, upper);
+        if c == 0 { None } else { self.kth_smallest(l..r, c-1) }
+    }
+    
+    /// `A[range]` に含まれる `lower` 以上の最小値を返す。
+    pub fn next_value(&self, range: impl RangeBounds<usize>, lower: u64) -> Option<u64> {
+        let [l, r] = to_bounds(range, self.len);
+        self.kth_smallest(l..r, self.count_less(l..r, lower))
+    }
+}
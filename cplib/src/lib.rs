@@ -15,6 +15,7 @@ pub mod algo {
     
     pub mod func;
     pub mod segtree_func;
+    pub mod mo;
     
     // pub mod seq;
 }
@@ -27,16 +28,37 @@ pub mod const_fp {
 
 pub mod ds {
     pub mod unionfind;
+    pub mod rollback_unionfind;
+    pub mod persistent_unionfind;
     pub mod csr;
     pub mod segtree;
+    pub mod fenwick;
+    pub mod segtree_2d;
+    pub mod sparse_table;
+    pub mod wavelet_matrix;
+    pub mod fenwick_2d;
+    pub mod bitset;
     
     pub mod trie;
     pub mod binary_trie;
+    pub mod interval_heap;
+    pub mod meldable_heap;
+    pub mod rangeset;
+    pub mod multiset;
+    pub mod sorted_set;
     pub mod persistent_segtree;
+    pub mod persistent_array;
+    pub mod persistent_queue;
+    pub mod pointer_segtree;
+    pub mod persistent_treap;
     
     pub mod foldable_deque;
     pub mod splay_tree;
+    pub mod ordered;
+    pub mod link_cut_tree;
+    pub mod euler_tour_tree;
     pub mod sparse_segtree;
+    pub mod li_chao_tree;
     pub mod convex_hull_trick_deque;
     pub mod convex_hull_trick_set;
     
@@ -79,6 +101,7 @@ pub mod traits {
     pub mod iter_util;
     pub mod map_init;
     pub mod vec_split;
+    pub mod select;
 }
 
 
@@ -88,6 +111,7 @@ pub mod util {
     pub mod macros;
     pub mod func;
     pub mod debug;
+    pub mod hash;
     
     pub mod global;
     pub mod time;
@@ -1,4 +1,5 @@
 use std::{fmt::{Debug, Display}, ops::{Add, Mul, Neg, Sub}};
+use crate::cplib::ds::unionfind::{Group, Abelian};
 
 pub const M: usize = 998244353;
 pub const R: usize = (1<<32) % M;
@@ -72,3 +73,25 @@ impl Display for Fp {
 impl Debug for Fp {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "{}", self.val()) }
 }
+
+
+
+/// [`Fp`] の加法群
+pub struct AddFp;
+impl Group for AddFp {
+    type T = Fp;
+    fn e() -> Fp { Fp::new(0) }
+    fn add(l: &Fp, r: &Fp) -> Fp { *l + *r }
+    fn inv(x: &Fp) -> Fp { -*x }
+    fn mul(x: &Fp, n: usize) -> Fp { *x * Fp::new(n) }
+}
+impl Abelian for AddFp {}
+
+/// [`Fp`] 上の一次関数 `x -> ax + b` (`a != 0`) の合成による群。`add(l, r)` は `l` の後に `r` を適用する関数である。
+pub struct AffineFp;
+impl Group for AffineFp {
+    type T = (Fp, Fp);
+    fn e() -> (Fp, Fp) { (Fp::new(1), Fp::new(0)) }
+    fn add(l: &(Fp, Fp), r: &(Fp, Fp)) -> (Fp, Fp) { (l.0 * r.0, r.0 * l.1 + r.1) }
+    fn inv(x: &(Fp, Fp)) -> (Fp, Fp) { let a = x.0.inv(); (a, -(a * x.1)) }
+}
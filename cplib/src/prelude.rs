@@ -11,5 +11,5 @@ pub use crate::cplib::{
     algo::func::*,
     math::modtable::O,
     traits::{grid::Grid, char_util::CharUtil, map_init::MapInit, vec_split::VecSplit, iter_util::IterUtil},
-    util::{output::{out, end}, debug::epr_table},
+    util::{output::{out, end}, debug::epr_table, hash::{FastHashMap, FastHashSet}},
 };
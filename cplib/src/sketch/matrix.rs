@@ -47,56 +47,69 @@ impl Matrix {
 
 
 
-// use crate::mylib::ds::bitset::BitSet;
+use crate::cplib::ds::bitset::BitSet;
 
-// #[derive(Clone)]
-// pub struct XorMatrix {
-//     data: Vec<BitSet>,
-//     size_h: usize,
-//     size_w: usize
-// }
+/// `F_2` 上の行列
+#[derive(Clone)]
+pub struct XorMatrix {
+    data: Vec<BitSet>,
+    size_h: usize,
+    size_w: usize
+}
 
-// impl XorMatrix {
-//     pub fn new(h: usize, w: usize, init: bool) -> Self {
-//         assert!(h <= 63 && w <= 63);
-//         Self { data: vec![BitSet::new(init, w); h], size_h: h, size_w: w }
-//     }
+impl XorMatrix {
+    pub fn new(h: usize, w: usize) -> Self {
+        Self { data: vec![BitSet::new(w); h], size_h: h, size_w: w }
+    }
+    
+    pub fn get(&self, i: usize, j: usize) -> bool { self.data[i][j] }
+    pub fn set(&mut self, i: usize, j: usize, value: bool) { self.data[i].set(j, value); }
+    pub fn row(&self, i: usize) -> &BitSet { &self.data[i] }
+    
+    /// 行基本変形で簡約化し、ピボットの列を返す。`is_extended` のときは最後の列をピボットにしない。
+    fn reduce(&mut self, is_extended: bool) -> Vec<usize> {
+        let w = self.size_w - if is_extended {1} else {0};
+        let mut pivot = vec![];
+        for j in 0..w {
+            let r = pivot.len();
+            let Some(p) = (r..self.size_h).find(|&i| self.data[i][j]) else { continue; };
+            self.data.swap(r, p);
+            let tmp = self.data[r].clone();
+            for i in 0..self.size_h {
+                if i != r && self.data[i][j] { self.data[i] ^= &tmp; }
+            }
+            pivot.push(j);
+        }
+        pivot
+    }
     
-//     pub fn set(&mut self, i: usize, j: usize, value: bool) { self.data[i].set(j, value); }
+    pub fn rank(&mut self) -> usize { self.reduce(false).len() }
     
-//     pub fn gauss_jordan(&mut self, is_extended: bool) -> Option<Vec<BitSet>> {
-//         let w = self.size_w - if is_extended {1} else {0};
-//         let mut j = usize::MAX;
-//         let (mut pivot, mut bases) = (vec![], vec![]);
+    /// 拡大係数行列とみなして `Ax = b` を解き、解の 1 つと解空間 (`Ax = 0` の解) の基底を返す。解がないとき `None` を返す。
+    /// 
+    /// `is_extended` が `false` のときは `b = 0` として、`Ax = 0` を解く。
+    pub fn gauss_jordan(&mut self, is_extended: bool) -> Option<(BitSet, Vec<BitSet>)> {
+        let pivot = self.reduce(is_extended);
+        let w = self.size_w - if is_extended {1} else {0};
+        if is_extended && (pivot.len()..self.size_h).any(|i| self.data[i][w]) { return None; }
         
-//         'main: for i in 0..self.size_h {
-//             loop {
-//                 j = j.wrapping_add(1);
-//                 if j == w { break 'main; }
-                
-//                 if let Some(mut p) = (i..self.size_h).find(|&i| self.data[i][j]) {
-//                     self.data.swap(i, p);
-//                     pivot.push(j);
-//                     p = i;
-                    
-//                     for i in 0..self.size_h {
-//                         if !self.data[i][j] || i == p { continue; }
-//                         let tmp = self.data[p];
-//                         self.data[i] ^= tmp;
-//                     }
-                    
-//                     break;
-//                 } else {
-//                     let mut v = BitSet::new(false, self.size_h);
-//                     v.set(j, true);
-//                     for (idx, &i) in pivot.iter().enumerate() {
-//                         if self.data[idx][j] { v.set(i, true); }
-//                     }
-//                     bases.push(v);
-//                 }
-//             }
-//         }
+        let mut x = BitSet::new(w);
+        if is_extended {
+            for (i, &j) in pivot.iter().enumerate() { x.set(j, self.data[i][w]); }
+        }
+        
+        let mut is_pivot = vec![false; w];
+        for &j in &pivot { is_pivot[j] = true; }
+        let mut bases = vec![];
+        for j in (0..w).filter(|&j| !is_pivot[j]) {
+            let mut v = BitSet::new(w);
+            v.set(j, true);
+            for (i, &p) in pivot.iter().enumerate() {
+                if self.data[i][j] { v.set(p, true); }
+            }
+            bases.push(v);
+        }
         
-//         Some(bases)
-//     }
-// }
+        Some((x, bases))
+    }
+}
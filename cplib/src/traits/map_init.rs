@@ -1,5 +1,6 @@
 use std::collections::{BTreeMap, HashMap};
-use std::hash::Hash;
+use std::hash::{BuildHasher, Hash};
+use crate::cplib::util::hash::U64Map;
 
 pub trait MapInit {
     type K;
@@ -8,7 +9,7 @@ pub trait MapInit {
     fn init_with(&mut self, key: Self::K, init: impl FnOnce() -> Self::V) -> &mut Self::V;
 }
 
-impl<K: Eq + Hash, V> MapInit for HashMap<K, V> {
+impl<K: Eq + Hash, V, S: BuildHasher> MapInit for HashMap<K, V, S> {
     type K = K;
     type V = V;
     fn init(&mut self, key: K, init: V) -> &mut V {
@@ -29,3 +30,14 @@ impl<K: Ord, V> MapInit for BTreeMap<K, V> {
         self.entry(key).or_insert_with(init)
     }
 }
+
+impl<V> MapInit for U64Map<V> {
+    type K = u64;
+    type V = V;
+    fn init(&mut self, key: u64, init: V) -> &mut V {
+        self.get_or_insert_with(key, || init)
+    }
+    fn init_with(&mut self, key: u64, init: impl FnOnce() -> Self::V) -> &mut V {
+        self.get_or_insert_with(key, init)
+    }
+}
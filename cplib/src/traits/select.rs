@@ -0,0 +1,14 @@
+/// 最小値・最大値のどちらを求めるかを型で選ぶための trait
+pub trait Select {
+    const MAX: bool;
+    /// `a` が `b` より真に良いか判定する。
+    fn better<T: PartialOrd>(a: &T, b: &T) -> bool { if Self::MAX { a > b } else { a < b } }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Min;
+impl Select for Min { const MAX: bool = false; }
+
+#[derive(Clone, Copy, Debug)]
+pub struct Max;
+impl Select for Max { const MAX: bool = true; }
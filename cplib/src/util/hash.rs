@@ -0,0 +1,198 @@
+use std::{collections::{HashMap, HashSet}, fmt::Debug, hash::{BuildHasher, Hasher}, sync::OnceLock, time::{SystemTime, UNIX_EPOCH}};
+
+
+
+pub fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e3779b97f4a7c15);
+    x = (x ^ x >> 30).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ x >> 27).wrapping_mul(0x94d049bb133111eb);
+    x ^ x >> 31
+}
+
+/// 実行ごとに異なる値を返す。同じ実行中は常に同じ値である。
+pub fn seed() -> u64 {
+    static SEED: OnceLock<u64> = OnceLock::new();
+    *SEED.get_or_init(|| {
+        let t = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos() as u64;
+        let p = &t as *const u64 as u64;
+        splitmix64(t ^ splitmix64(p))
+    })
+}
+
+
+
+/// splitmix64 によるハッシュ関数。初期値は [`seed`] によってランダムに決まるので、anti-hash テストを作られにくい。
+#[derive(Clone, Copy)]
+pub struct FastHasher(u64);
+
+impl Hasher for FastHasher {
+    fn finish(&self) -> u64 { self.0 }
+    
+    fn write(&mut self, bytes: &[u8]) {
+        self.write_u64(bytes.len() as u64);
+        for c in bytes.chunks(8) {
+            let mut buf = [0; 8];
+            buf[..c.len()].copy_from_slice(c);
+            self.write_u64(u64::from_le_bytes(buf));
+        }
+    }
+    
+    fn write_u8(&mut self, i: u8) { self.write_u64(i as u64); }
+    fn write_u16(&mut self, i: u16) { self.write_u64(i as u64); }
+    fn write_u32(&mut self, i: u32) { self.write_u64(i as u64); }
+    fn write_u64(&mut self, i: u64) { self.0 = splitmix64(self.0 ^ i); }
+    fn write_usize(&mut self, i: usize) { self.write_u64(i as u64); }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct FastBuildHasher(u64);
+
+impl Default for FastBuildHasher {
+    fn default() -> Self { Self(seed()) }
+}
+
+impl BuildHasher for FastBuildHasher {
+    type Hasher = FastHasher;
+    fn build_hasher(&self) -> Self::Hasher { FastHasher(self.0) }
+}
+
+/// [`FastBuildHasher`] を用いた `HashMap` 。`new` の代わりに `default` で作成する。
+pub type FastHashMap<K, V> = HashMap<K, V, FastBuildHasher>;
+
+/// [`FastBuildHasher`] を用いた `HashSet` 。`new` の代わりに `default` で作成する。
+pub type FastHashSet<K> = HashSet<K, FastBuildHasher>;
+
+
+
+/// キーが `u64` のハッシュマップ (オープンアドレス法・線形探索)
+/// 
+/// 負荷率を `1/2` 以下に保つ。ハッシュ値は [`seed`] を用いてランダムに決まる。
+/// 
+/// # Implemented Traits
+/// 
+/// - `Clone`, `Debug`, `FromIterator<(u64, V)>`, [`MapInit`](crate::cplib::traits::map_init::MapInit)
+#[derive(Clone)]
+pub struct U64Map<V> {
+    slots: Vec<Option<(u64, V)>>,
+    len: usize,
+    seed: u64,
+}
+
+impl<V> U64Map<V> {
+    pub fn new() -> Self { Self::with_capacity(0) }
+    
+    /// 要素数 `n` まで再構築が起きないものを作成する。
+    pub fn with_capacity(n: usize) -> Self {
+        let cap = (n * 2).next_power_of_two().max(8);
+        Self { slots: (0..cap).map(|_| None).collect(), len: 0, seed: seed() }
+    }
+    
+    pub fn len(&self) -> usize { self.len }
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+    
+    fn mask(&self) -> usize { self.slots.len() - 1 }
+    fn home(&self, key: u64) -> usize { splitmix64(key ^ self.seed) as usize & self.mask() }
+    
+    /// `key` を持つ位置 (`Ok`) または `key` を置くべき空の位置 (`Err`) を返す。
+    fn find(&self, key: u64) -> Result<usize, usize> {
+        let mut i = self.home(key);
+        loop {
+            match &self.slots[i] {
+                None => return Err(i),
+                Some((k, _)) if *k == key => return Ok(i),
+                _ => i = i+1 & self.mask()
+            }
+        }
+    }
+    
+    fn grow(&mut self) {
+        let cap = self.slots.len() * 2;
+        let old = std::mem::replace(&mut self.slots, (0..cap).map(|_| None).collect());
+        for (k, v) in old.into_iter().flatten() {
+            let Err(i) = self.find(k) else { unreachable!() };
+            self.slots[i] = Some((k, v));
+        }
+    }
+    
+    pub fn contains_key(&self, key: u64) -> bool { self.find(key).is_ok() }
+    
+    pub fn get(&self, key: u64) -> Option<&V> {
+        let i = self.find(key).ok()?;
+        self.slots[i].as_ref().map(|(_, v)| v)
+    }
+    
+    pub fn get_mut(&mut self, key: u64) -> Option<&mut V> {
+        let i = self.find(key).ok()?;
+        self.slots[i].as_mut().map(|(_, v)| v)
+    }
+    
+    /// `key` の値への可変参照を返す。`key` が存在しないときは `f()` を挿入する。
+    pub fn get_or_insert_with(&mut self, key: u64, f: impl FnOnce() -> V) -> &mut V {
+        let i = match self.find(key) {
+            Ok(i) => i,
+            Err(_) if (self.len + 1) * 2 > self.slots.len() => {
+                self.grow();
+                self.len += 1;
+                let Err(i) = self.find(key) else { unreachable!() };
+                self.slots[i] = Some((key, f()));
+                i
+            }
+            Err(i) => { self.len += 1; self.slots[i] = Some((key, f())); i }
+        };
+        &mut self.slots[i].as_mut().unwrap().1
+    }
+    
+    /// `key` に `value` を設定し、以前の値を返す。
+    pub fn insert(&mut self, key: u64, value: V) -> Option<V> {
+        if let Some(v) = self.get_mut(key) { return Some(std::mem::replace(v, value)); }
+        self.get_or_insert_with(key, || value);
+        None
+    }
+    
+    pub fn remove(&mut self, key: u64) -> Option<V> {
+        let mut i = self.find(key).ok()?;
+        let res = self.slots[i].take().map(|(_, v)| v);
+        self.len -= 1;
+        // 後ろの要素を詰める
+        let mut j = i;
+        loop {
+            j = j+1 & self.mask();
+            let Some((k, _)) = &self.slots[j] else { break; };
+            let h = self.home(*k);
+            // h が巡回区間 (i, j] に含まれないならば、j の要素を i に移す
+            if (j.wrapping_sub(h) & self.mask()) >= (j.wrapping_sub(i) & self.mask()) {
+                self.slots[i] = self.slots[j].take();
+                i = j;
+            }
+        }
+        res
+    }
+    
+    pub fn clear(&mut self) {
+        for s in &mut self.slots { *s = None; }
+        self.len = 0;
+    }
+    
+    /// 要素を順不同で返す。
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &V)> {
+        self.slots.iter().flatten().map(|(k, v)| (*k, v))
+    }
+}
+
+impl<V> Default for U64Map<V> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<V> FromIterator<(u64, V)> for U64Map<V> {
+    fn from_iter<T: IntoIterator<Item = (u64, V)>>(iter: T) -> Self {
+        let mut res = Self::new();
+        for (k, v) in iter { res.insert(k, v); }
+        res
+    }
+}
+
+impl<V: Debug> Debug for U64Map<V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}